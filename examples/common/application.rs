@@ -52,18 +52,30 @@
 //
 use microui_redux::*;
 
-use sdl2::{Sdl, VideoSubsystem};
+use sdl2::{GameControllerSubsystem, Sdl, TimerSubsystem, VideoSubsystem};
+use sdl2::controller::{Button, GameController};
 use sdl2::event::{Event, WindowEvent};
 use sdl2::keyboard::Keycode;
+use sdl2::mouse::{Cursor, SystemCursor};
 use sdl2::video::{GLContext, GLProfile, Window};
 
 use super::*;
 
 type MicroUI = microui_redux::Context<(), GLRenderer>;
+const RECORDING_PATH: &str = "input-recording.txt";
 pub struct Application<S> {
     state: S,
     sdl_ctx: Sdl,
-    _sdl_vid: VideoSubsystem,
+    sdl_vid: VideoSubsystem,
+    sdl_gc: GameControllerSubsystem,
+    sdl_timer: TimerSubsystem,
+    controllers: Vec<GameController>,
+    // physical keys held down, the left and right modifiers map to one key released with the last of them
+    keys_held: Vec<Keycode>,
+    // the SDL cursor must stay alive while it is set
+    cursor: Option<(CursorIcon, Cursor)>,
+    // F9 toggles recording to RECORDING_PATH, F10 replays it, live input is ignored meanwhile
+    player: Option<Player>,
     gl_ctx: GLContext,
     window: Window,
     ctx: MicroUI,
@@ -73,6 +85,11 @@ impl<S> Application<S> {
     pub fn new<F: FnOnce(&mut MicroUI) -> S>(atlas: AtlasHandle, init_state: F) -> Result<Self, String> {
         let sdl_ctx = sdl2::init().unwrap();
         let video = sdl_ctx.video().unwrap();
+        let game_controller = sdl_ctx.game_controller().unwrap();
+        let timer = sdl_ctx.timer().unwrap();
+
+        // touches are fed to microui directly, don't let SDL turn them into mouse events as well
+        sdl2::hint::set("SDL_TOUCH_MOUSE_EVENTS", "0");
 
         let gl_attr = video.gl_attr();
         gl_attr.set_context_profile(GLProfile::GLES);
@@ -97,7 +114,13 @@ impl<S> Application<S> {
         Ok(Self {
             state: init_state(&mut ctx),
             sdl_ctx,
-            _sdl_vid: video,
+            sdl_vid: video,
+            sdl_gc: game_controller,
+            sdl_timer: timer,
+            controllers: Vec::new(),
+            keys_held: Vec::new(),
+            cursor: None,
+            player: None,
             gl_ctx,
             window,
             ctx,
@@ -112,6 +135,15 @@ impl<S> Application<S> {
             let (width, height) = self.window.size();
 
             self.ctx.clear(width as i32, height as i32, color(0x7F, 0x7F, 0x7F, 255));
+            match &mut self.player {
+                Some(player) => {
+                    let playing = player.feed(&mut self.ctx.input.borrow_mut());
+                    if !playing {
+                        self.player = None;
+                    }
+                }
+                None => self.ctx.input.borrow_mut().set_time(self.sdl_timer.ticks64()),
+            }
 
             fn map_mouse_button(sdl_mb: sdl2::mouse::MouseButton) -> microui_redux::MouseButton {
                 match sdl_mb {
@@ -122,21 +154,108 @@ impl<S> Application<S> {
                 }
             }
 
-            fn map_keymode(sdl_km: sdl2::keyboard::Mod, sdl_kc: Option<sdl2::keyboard::Keycode>) -> microui_redux::KeyMode {
-                match (sdl_km, sdl_kc) {
-                    (sdl2::keyboard::Mod::LALTMOD, _) | (sdl2::keyboard::Mod::RALTMOD, _) => microui_redux::KeyMode::ALT,
-                    (sdl2::keyboard::Mod::LCTRLMOD, _) | (sdl2::keyboard::Mod::RCTRLMOD, _) => microui_redux::KeyMode::CTRL,
-                    (sdl2::keyboard::Mod::LSHIFTMOD, _) | (sdl2::keyboard::Mod::RSHIFTMOD, _) => microui_redux::KeyMode::SHIFT,
-                    (_, Some(sdl2::keyboard::Keycode::Backspace)) => microui_redux::KeyMode::BACKSPACE,
-                    (_, Some(sdl2::keyboard::Keycode::Return)) => microui_redux::KeyMode::RETURN,
-                    _ => microui_redux::KeyMode::NONE,
+            fn map_key(sdl_kc: Option<Keycode>) -> Option<microui_redux::Key> {
+                use microui_redux::Key;
+                Some(match sdl_kc? {
+                    Keycode::LShift | Keycode::RShift => Key::Shift,
+                    Keycode::LCtrl | Keycode::RCtrl => Key::Ctrl,
+                    Keycode::LAlt | Keycode::RAlt => Key::Alt,
+                    Keycode::Backspace => Key::Backspace,
+                    Keycode::Return => Key::Return,
+                    Keycode::Tab => Key::Tab,
+                    Keycode::Escape => Key::Escape,
+                    Keycode::Space => Key::Space,
+                    Keycode::Delete => Key::Delete,
+                    Keycode::Insert => Key::Insert,
+                    Keycode::Home => Key::Home,
+                    Keycode::End => Key::End,
+                    Keycode::PageUp => Key::PageUp,
+                    Keycode::PageDown => Key::PageDown,
+                    Keycode::Left => Key::Left,
+                    Keycode::Right => Key::Right,
+                    Keycode::Up => Key::Up,
+                    Keycode::Down => Key::Down,
+                    Keycode::F1 => Key::F1,
+                    Keycode::F2 => Key::F2,
+                    Keycode::F3 => Key::F3,
+                    Keycode::F4 => Key::F4,
+                    Keycode::F5 => Key::F5,
+                    Keycode::F6 => Key::F6,
+                    Keycode::F7 => Key::F7,
+                    Keycode::F8 => Key::F8,
+                    Keycode::F9 => Key::F9,
+                    Keycode::F10 => Key::F10,
+                    Keycode::F11 => Key::F11,
+                    Keycode::F12 => Key::F12,
+                    Keycode::Num0 => Key::Num0,
+                    Keycode::Num1 => Key::Num1,
+                    Keycode::Num2 => Key::Num2,
+                    Keycode::Num3 => Key::Num3,
+                    Keycode::Num4 => Key::Num4,
+                    Keycode::Num5 => Key::Num5,
+                    Keycode::Num6 => Key::Num6,
+                    Keycode::Num7 => Key::Num7,
+                    Keycode::Num8 => Key::Num8,
+                    Keycode::Num9 => Key::Num9,
+                    Keycode::A => Key::A,
+                    Keycode::B => Key::B,
+                    Keycode::C => Key::C,
+                    Keycode::D => Key::D,
+                    Keycode::E => Key::E,
+                    Keycode::F => Key::F,
+                    Keycode::G => Key::G,
+                    Keycode::H => Key::H,
+                    Keycode::I => Key::I,
+                    Keycode::J => Key::J,
+                    Keycode::K => Key::K,
+                    Keycode::L => Key::L,
+                    Keycode::M => Key::M,
+                    Keycode::N => Key::N,
+                    Keycode::O => Key::O,
+                    Keycode::P => Key::P,
+                    Keycode::Q => Key::Q,
+                    Keycode::R => Key::R,
+                    Keycode::S => Key::S,
+                    Keycode::T => Key::T,
+                    Keycode::U => Key::U,
+                    Keycode::V => Key::V,
+                    Keycode::W => Key::W,
+                    Keycode::X => Key::X,
+                    Keycode::Y => Key::Y,
+                    Keycode::Z => Key::Z,
+                    _ => return None,
+                })
+            }
+
+            fn map_controller_button(button: Button) -> microui_redux::GamepadButton {
+                match button {
+                    Button::DPadUp => microui_redux::GamepadButton::UP,
+                    Button::DPadDown => microui_redux::GamepadButton::DOWN,
+                    Button::DPadLeft => microui_redux::GamepadButton::LEFT,
+                    Button::DPadRight => microui_redux::GamepadButton::RIGHT,
+                    Button::A => microui_redux::GamepadButton::ACCEPT,
+                    Button::B => microui_redux::GamepadButton::CANCEL,
+                    Button::LeftShoulder => microui_redux::GamepadButton::LEFT_SHOULDER,
+                    Button::RightShoulder => microui_redux::GamepadButton::RIGHT_SHOULDER,
+                    _ => microui_redux::GamepadButton::NONE,
                 }
             }
 
             for event in event_pump.poll_iter() {
                 match event {
-                    Event::Quit { .. } | Event::KeyDown { keycode: Some(Keycode::Escape), .. } => break 'running,
+                    Event::Quit { .. } => break 'running,
                     Event::Window { win_event: WindowEvent::Close, .. } => break 'running,
+                    Event::KeyDown {
+                        keycode: Some(Keycode::F9),
+                        repeat: false,
+                        ..
+                    } => self.toggle_recording(),
+                    Event::KeyDown {
+                        keycode: Some(Keycode::F10),
+                        repeat: false,
+                        ..
+                    } => self.replay(),
+                    _ if self.player.is_some() => {}
                     Event::MouseMotion { x, y, .. } => self.ctx.input.borrow_mut().mousemove(x, y),
                     Event::MouseWheel { y, .. } => self.ctx.input.borrow_mut().scroll(0, y * -30),
                     Event::MouseButtonDown { x, y, mouse_btn, .. } => {
@@ -147,27 +266,110 @@ impl<S> Application<S> {
                         let mb = map_mouse_button(mouse_btn);
                         self.ctx.input.borrow_mut().mouseup(x, y, mb);
                     }
-                    Event::KeyDown { keymod, keycode, .. } => {
-                        let km = map_keymode(keymod, keycode);
-                        self.ctx.input.borrow_mut().keydown(km);
+                    Event::KeyDown { keycode, .. } => {
+                        if let Some(key) = map_key(keycode) {
+                            let keycode = keycode.unwrap();
+                            if !self.keys_held.contains(&keycode) {
+                                self.keys_held.push(keycode);
+                            }
+                            self.ctx.input.borrow_mut().keydown(key);
+                        }
                     }
-                    Event::KeyUp { keymod, keycode, .. } => {
-                        let km = map_keymode(keymod, keycode);
-                        self.ctx.input.borrow_mut().keyup(km);
+                    Event::KeyUp { keycode, .. } => {
+                        if let Some(key) = map_key(keycode) {
+                            self.keys_held.retain(|k| Some(*k) != keycode);
+                            if !self.keys_held.iter().any(|k| map_key(Some(*k)) == Some(key)) {
+                                self.ctx.input.borrow_mut().keyup(key);
+                            }
+                        }
                     }
                     Event::TextInput { text, .. } => {
                         self.ctx.input.borrow_mut().text(text.as_str());
                     }
+                    Event::FingerDown { finger_id, x, y, .. } => {
+                        let (x, y) = ((x * width as f32) as i32, (y * height as f32) as i32);
+                        self.ctx.input.borrow_mut().touchdown(finger_id as u64, x, y);
+                    }
+                    Event::FingerMotion { finger_id, x, y, .. } => {
+                        let (x, y) = ((x * width as f32) as i32, (y * height as f32) as i32);
+                        self.ctx.input.borrow_mut().touchmove(finger_id as u64, x, y);
+                    }
+                    Event::FingerUp { finger_id, x, y, .. } => {
+                        let (x, y) = ((x * width as f32) as i32, (y * height as f32) as i32);
+                        self.ctx.input.borrow_mut().touchup(finger_id as u64, x, y);
+                    }
+                    Event::ControllerDeviceAdded { which, .. } => {
+                        if let Ok(controller) = self.sdl_gc.open(which) {
+                            self.controllers.push(controller);
+                        }
+                    }
+                    Event::ControllerButtonDown { button, .. } => {
+                        self.ctx.input.borrow_mut().gamepaddown(map_controller_button(button));
+                    }
+                    Event::ControllerButtonUp { button, .. } => {
+                        self.ctx.input.borrow_mut().gamepadup(map_controller_button(button));
+                    }
+                    Event::TextEditing { text, start, length, .. } => {
+                        self.ctx.input.borrow_mut().composition(text.as_str(), start as usize, length as usize);
+                    }
 
                     _ => {}
                 }
             }
 
             f(&mut self.ctx, &mut self.state);
+            if let Some(r) = self.ctx.caret_rect() {
+                self.sdl_vid.text_input().set_rect(sdl2::rect::Rect::new(r.x, r.y, r.width as _, r.height as _));
+            }
+            self.update_cursor();
             self.ctx.flush();
             self.window.gl_swap_window();
 
             ::std::thread::sleep(::std::time::Duration::new(0, 1_000_000_000u32 / 60));
         }
     }
+
+    fn toggle_recording(&mut self) {
+        let mut input = self.ctx.input.borrow_mut();
+        if input.is_recording() {
+            if let Some(recording) = input.stop_recording() {
+                if let Err(e) = recording.save(RECORDING_PATH) {
+                    eprintln!("failed to save {}: {}", RECORDING_PATH, e);
+                }
+            }
+        } else if self.player.is_none() {
+            input.start_recording();
+        }
+    }
+
+    fn replay(&mut self) {
+        if self.ctx.input.borrow().is_recording() {
+            return;
+        }
+        match Recording::load(RECORDING_PATH) {
+            Ok(recording) => self.player = Some(Player::new(recording)),
+            Err(e) => eprintln!("failed to load {}: {}", RECORDING_PATH, e),
+        }
+    }
+
+    fn update_cursor(&mut self) {
+        let icon = self.ctx.cursor();
+        if self.cursor.as_ref().is_some_and(|(c, _)| *c == icon) {
+            return;
+        }
+        let system = match icon {
+            CursorIcon::Arrow => SystemCursor::Arrow,
+            CursorIcon::IBeam => SystemCursor::IBeam,
+            CursorIcon::ResizeNS => SystemCursor::SizeNS,
+            CursorIcon::ResizeEW => SystemCursor::SizeWE,
+            CursorIcon::ResizeNWSE => SystemCursor::SizeNWSE,
+            CursorIcon::Hand => SystemCursor::Hand,
+            CursorIcon::Move => SystemCursor::SizeAll,
+            CursorIcon::NotAllowed => SystemCursor::No,
+        };
+        if let Ok(cursor) = Cursor::from_system(system) {
+            cursor.set();
+            self.cursor = Some((icon, cursor));
+        }
+    }
 }
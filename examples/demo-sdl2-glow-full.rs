@@ -19,31 +19,59 @@ type Context = microui_redux::Context<(), GLRenderer>;
 struct State<'a> {
     rng: Rc<RefCell<ThreadRng>>,
     slots: Vec<SlotId>,
-    label_colors: [LabelColor<'a>; 15],
+    label_colors: [LabelColor<'a>; 17],
     bg: [Real; 3],
     logbuf: String,
     logbuf_updated: bool,
     submit_buf: String,
     checks: [bool; 3],
+    all_checks: CheckState,
+    password_buf: String,
+    hex_buf: String,
+    name_buf: String,
+    dnd_items: Vec<String>,
     style: Style,
 
     demo_window: Option<WindowHandle<()>>,
     style_window: Option<WindowHandle<()>>,
     log_window: Option<WindowHandle<()>>,
     popup_window: Option<WindowHandle<()>>,
+    confirm_window: Option<WindowHandle<()>>,
     log_output: Option<ContainerHandle<()>>,
+    rows_panel: Option<ContainerHandle<()>>,
+    files_panel: Option<ContainerHandle<()>>,
 
     window_header: NodeState,
     test_buttons_header: NodeState,
     background_header: NodeState,
     tree_and_text_header: NodeState,
     slot_header: NodeState,
+    text_fields_header: NodeState,
+    dnd_header: NodeState,
+    list_header: NodeState,
+    table_header: NodeState,
+    tabs_header: NodeState,
+    progress_header: NodeState,
+    numbers_header: NodeState,
     test1_tn: NodeState,
     test1a_tn: NodeState,
     test1b_tn: NodeState,
     test2_tn: NodeState,
     test3_tn: NodeState,
     open_popup: bool,
+    quiet_button2: bool,
+    confirm_clear: bool,
+    planet: usize,
+    rows: ListSelection,
+    files: Vec<(String, u32, &'static str)>,
+    files_table: TableState,
+    notes_tab: bool,
+    dark: bool,
+    size: u32,
+    progress: Real,
+    count: i64,
+    scale: f64,
+    position: Vec3f,
 }
 
 #[derive(Copy, Clone)]
@@ -110,6 +138,14 @@ impl<'a> State<'a> {
                     label: "scrollthumb:",
                     idx: ControlColor::ScrollThumb,
                 },
+                LabelColor {
+                    label: "error:",
+                    idx: ControlColor::Error,
+                },
+                LabelColor {
+                    label: "focusring:",
+                    idx: ControlColor::FocusRing,
+                },
                 LabelColor { label: "", idx: ControlColor::Text },
             ],
             bg: [90.0, 95.0, 100.0],
@@ -117,18 +153,33 @@ impl<'a> State<'a> {
             logbuf_updated: false,
             submit_buf: String::new(),
             checks: [false, true, false],
+            all_checks: CheckState::Mixed,
+            password_buf: String::new(),
+            hex_buf: String::new(),
+            name_buf: String::new(),
+            dnd_items: ["Apples", "Oranges", "Pears", "Plums"].iter().map(|s| s.to_string()).collect(),
 
             demo_window: None,
             style_window: None,
             log_window: None,
             popup_window: None,
+            confirm_window: None,
             log_output: None,
+            rows_panel: None,
+            files_panel: None,
 
             window_header: NodeState::Closed,
             test_buttons_header: NodeState::Expanded,
             tree_and_text_header: NodeState::Expanded,
             background_header: NodeState::Expanded,
             slot_header: NodeState::Expanded,
+            text_fields_header: NodeState::Closed,
+            dnd_header: NodeState::Closed,
+            list_header: NodeState::Closed,
+            table_header: NodeState::Closed,
+            tabs_header: NodeState::Closed,
+            progress_header: NodeState::Closed,
+            numbers_header: NodeState::Closed,
 
             test1_tn: NodeState::Closed,
             test1a_tn: NodeState::Closed,
@@ -136,6 +187,34 @@ impl<'a> State<'a> {
             test2_tn: NodeState::Closed,
             test3_tn: NodeState::Closed,
             open_popup: false,
+            quiet_button2: false,
+            confirm_clear: false,
+            planet: usize::MAX,
+            rows: ListSelection::new(SelectionMode::Multi),
+            files: (0..1000)
+                .map(|i| {
+                    (
+                        format!("file_{:04}", i),
+                        (i * 7919) % 100_000,
+                        ["Image", "Sound", "Mesh", "Script"][i as usize % 4],
+                    )
+                })
+                .collect(),
+            files_table: TableState::new(
+                vec![
+                    TableColumn::new("Name", 100).sortable(),
+                    TableColumn::new("Size", 60).sortable(),
+                    TableColumn::new("Kind", 60),
+                ],
+                SelectionMode::Multi,
+            ),
+            notes_tab: true,
+            dark: true,
+            size: 1,
+            progress: 0.42,
+            count: 100_000_007,
+            scale: 1.0,
+            position: Vec3f::new(0.0, 1.5, -2.0),
         }
     }
 
@@ -150,13 +229,41 @@ impl<'a> State<'a> {
     }
 
     fn test_window(&mut self, ctx: &mut Context) {
-        ctx.window(&mut self.demo_window.as_mut().unwrap().clone(), WidgetOption::NONE, |container| {
+        ctx.window(&mut self.demo_window.as_mut().unwrap().clone(), WidgetOption::MENU_BAR, |container| {
             let mut win = container.rect;
             win.width = if win.width > 240 { win.width } else { 240 };
             win.height = if win.height > 300 { win.height } else { 300 };
 
             container.rect = win;
 
+            container.menu_bar(|bar| {
+                bar.menu("File", |menu| {
+                    if menu.menu_item_ex("Open Popup", Some("open_popup"), WidgetOption::NONE) {
+                        self.open_popup = true;
+                    }
+                    if menu.menu_item_ex("Clear Log", Some("clear_log"), WidgetOption::NONE) {
+                        self.confirm_clear = true;
+                    }
+                    menu.menu_separator();
+                    menu.menu_item_ex("Save Log", None, WidgetOption::NO_INTERACT);
+                });
+                bar.menu("View", |menu| {
+                    menu.menu_check("Quiet Button 2", &mut self.quiet_button2);
+                    menu.submenu("Headers", |menu| {
+                        if menu.menu_item("Expand All") {
+                            self.test_buttons_header = NodeState::Expanded;
+                            self.tree_and_text_header = NodeState::Expanded;
+                            self.text_fields_header = NodeState::Expanded;
+                        }
+                        if menu.menu_item("Collapse All") {
+                            self.test_buttons_header = NodeState::Closed;
+                            self.tree_and_text_header = NodeState::Closed;
+                            self.text_fields_header = NodeState::Closed;
+                        }
+                    });
+                });
+            });
+
             let mut buff = String::new();
 
             self.window_header = container.header("Window Info", self.window_header, |container| {
@@ -178,12 +285,31 @@ impl<'a> State<'a> {
             self.test_buttons_header = container.header("Test Buttons", self.test_buttons_header, |container| {
                 container.set_row_widths_height(&[86, -110, -1], 0);
                 container.label("Test buttons 1:");
-                if !container.button_ex("Button 1", None, WidgetOption::ALIGN_CENTER).is_none() {
+                let res = container.button_ex("Button 1", None, WidgetOption::ALIGN_CENTER);
+                container.tooltip("Click or double click");
+                if res.is_double_clicked() {
+                    self.write_log("Double clicked button 1");
+                } else if !res.is_none() {
                     self.write_log("Pressed button 1");
                 }
-                if !container.button_ex("Button 2", None, WidgetOption::ALIGN_CENTER).is_none() {
+                if !container.button_ex("Button 2", None, WidgetOption::ALIGN_CENTER).is_none() && !self.quiet_button2 {
                     self.write_log("Pressed button 2");
                 }
+                container.context_menu_for_last(|menu| {
+                    if menu.menu_item("Press") {
+                        self.write_log("Pressed button 2 from its menu");
+                    }
+                    menu.menu_check("Quiet", &mut self.quiet_button2);
+                    menu.menu_separator();
+                    menu.submenu("Say", |menu| {
+                        if menu.menu_item("Hello") {
+                            self.write_log("Hello");
+                        }
+                        if menu.menu_item("Goodbye") {
+                            self.write_log("Goodbye");
+                        }
+                    });
+                });
                 container.label("Test buttons 2:");
                 if !container.button_ex("Button 3", None, WidgetOption::ALIGN_CENTER).is_none() {
                     self.write_log("Pressed button 3");
@@ -191,6 +317,15 @@ impl<'a> State<'a> {
                 if !container.button_ex("Popup", None, WidgetOption::ALIGN_CENTER).is_none() {
                      self.open_popup = true;
                 }
+                container.tooltip_with(|c| {
+                    c.set_row_widths_height(&[160], 0);
+                    c.label("Opens a popup window");
+                    c.label("Click outside to close it");
+                });
+                container.set_row_widths_height(&[-1], 0);
+                if container.button_shortcut("Clear Log", "clear_log", WidgetOption::ALIGN_CENTER).is_submitted() {
+                    self.confirm_clear = true;
+                }
             });
             self.tree_and_text_header = container.header("Tree and Text", self.tree_and_text_header, |container| {
                 container.set_row_widths_height(&[140, -1], 0);
@@ -224,10 +359,18 @@ impl<'a> State<'a> {
                             self.write_log("Pressed button 6");
                         }
                     });
-                    self.test3_tn = container.treenode("Test 3", self.test3_tn, |container| {
+                    self.test3_tn = container.treenode_ex("Test 3 (double click)", self.test3_tn, WidgetOption::DOUBLE_CLICK, |container| {
+                        self.all_checks = CheckState::from_children(self.checks);
+                        if container.checkbox_tristate("All", &mut self.all_checks).is_changed() {
+                            self.checks = [self.all_checks.is_checked(); 3];
+                        }
                         container.checkbox("Checkbox 1", &mut self.checks[0]);
                         container.checkbox("Checkbox 2", &mut self.checks[1]);
                         container.checkbox("Checkbox 3", &mut self.checks[2]);
+                        container.toggle("Dark", &mut self.dark);
+                        container.radio("Small", &mut self.size, 0);
+                        container.radio("Medium", &mut self.size, 1);
+                        container.radio("Large", &mut self.size, 2);
                     });
                 });
                 container.column(|container| {
@@ -271,6 +414,124 @@ impl<'a> State<'a> {
 
 
             });
+
+            self.text_fields_header = container.header("Text Fields", self.text_fields_header, |container| {
+                container.set_row_widths_height(&[70, -1], 0);
+                container.label("Password:");
+                container.textbox_ex(&mut self.password_buf, WidgetOption::PASSWORD);
+                container.label("Hex (6):");
+                let hex = TextField { filter: TextFilter::Hex, max_length: Some(6), validate: None };
+                container.textbox_field_ex(&mut self.hex_buf, &hex, WidgetOption::NONE);
+                container.label("Name:");
+                let not_empty = |s: &str| !s.trim().is_empty();
+                let name = TextField { validate: Some(&not_empty), ..Default::default() };
+                if container.textbox_field_ex(&mut self.name_buf, &name, WidgetOption::NONE).is_submitted() {
+                    self.write_log(format!("Hello {}", self.name_buf).as_str());
+                }
+                container.label("Planet:");
+                let planets = ["Mercury", "Venus", "Earth", "Mars", "Jupiter", "Saturn", "Uranus", "Neptune", "Pluto"];
+                if container.combo("Pick a planet", &mut self.planet, &planets).is_changed() {
+                    self.write_log(format!("Picked {}", planets[self.planet]).as_str());
+                }
+            });
+
+            self.dnd_header = container.header("Drag and Drop", self.dnd_header, |container| {
+                container.set_row_widths_height(&[-1], 0);
+                let mut moved = None;
+                for (i, item) in self.dnd_items.iter().enumerate() {
+                    container.button_ex(item, None, WidgetOption::NONE);
+                    container.drag_source(item, || i);
+                    if let Some(from) = container.drop_target::<usize>() {
+                        moved = Some((from, i));
+                    }
+                }
+                if let Some((from, to)) = moved {
+                    let item = self.dnd_items.remove(from);
+                    self.dnd_items.insert(to, item);
+                }
+            });
+
+            self.list_header = container.header("Long List", self.list_header, |container| {
+                container.set_row_widths_height(&[-1], 120);
+                let panel = self.rows_panel.as_mut().unwrap();
+                let res = container.list_box(panel, &mut self.rows, 100_000, |i| format!("Row {}", i));
+                if res.is_submitted() {
+                    if let Some(i) = self.rows.cursor() {
+                        self.write_log(format!("Opened row {}", i).as_str());
+                    }
+                } else if res.is_changed() {
+                    self.write_log(format!("{} rows selected", self.rows.len()).as_str());
+                }
+            });
+
+            self.table_header = container.header("Table", self.table_header, |container| {
+                container.set_row_widths_height(&[-1], 150);
+                let panel = self.files_panel.as_mut().unwrap();
+                let files = &self.files;
+                container.table(panel, &mut self.files_table, files.len(), |container, row, column| {
+                    let (name, size, kind) = &files[row];
+                    match column {
+                        0 => container.label(name),
+                        1 => container.label(format!("{} KB", size).as_str()),
+                        _ => container.label(kind),
+                    }
+                });
+                if self.files_table.is_sort_changed() {
+                    if let Some((column, order)) = self.files_table.sort() {
+                        match column {
+                            0 => self.files.sort_by(|a, b| a.0.cmp(&b.0)),
+                            _ => self.files.sort_by_key(|f| f.1),
+                        }
+                        if !order.is_ascending() {
+                            self.files.reverse();
+                        }
+                        self.files_table.selection.clear();
+                    }
+                }
+            });
+
+            self.progress_header = container.header("Progress", self.progress_header, |container| {
+                container.set_row_widths_height(&[-1], 0);
+                container.slider_ex(&mut self.progress, 0.0, 1.0, 0.01, 2, WidgetOption::ALIGN_CENTER);
+                container.progress_bar(self.progress);
+                container.set_row_widths_height(&[-30, -1], 0);
+                container.busy_bar();
+                container.spinner();
+            });
+
+            self.numbers_header = container.header("Numbers", self.numbers_header, |container| {
+                container.set_row_widths_height(&[70, -1], 0);
+                container.label("Count:");
+                container.number_ex(&mut self.count, 1.0, 0, WidgetOption::ALIGN_CENTER);
+                container.label("Scale:");
+                container.slider_ex(&mut self.scale, 0.0, 4.0, 0.25, 2, WidgetOption::ALIGN_CENTER);
+                container.label("Position:");
+                container.number_vec3_ex("position", &mut self.position, 0.1, 1, WidgetOption::NONE);
+            });
+
+            self.tabs_header = container.header("Tabs", self.tabs_header, |container| {
+                container.tab_bar("inspector", |tabs| {
+                    tabs.tab("General", |container| {
+                        container.set_row_widths_height(&[70, -1], 0);
+                        container.label("Name:");
+                        container.textbox_ex(&mut self.name_buf, WidgetOption::NONE);
+                    });
+                    tabs.tab("Checks", |container| {
+                        container.set_row_widths_height(&[-1], 0);
+                        for (i, check) in self.checks.iter_mut().enumerate() {
+                            container.checkbox(format!("Check {}", i + 1).as_str(), check);
+                        }
+                    });
+                    tabs.closable_tab("Notes", &mut self.notes_tab, |container| {
+                        container.set_row_widths_height(&[-1], 0);
+                        container.label("Close this tab with its button");
+                    });
+                });
+                container.set_row_widths_height(&[-1], 0);
+                if !self.notes_tab && !container.button_ex("Reopen Notes", None, WidgetOption::ALIGN_CENTER).is_none() {
+                    self.notes_tab = true;
+                }
+            });
         });
 
         if self.open_popup {
@@ -294,6 +555,11 @@ impl<'a> State<'a> {
                 let container = &mut container_handle.inner_mut();
                 let mut scroll = container.scroll;
                 let content_size = container.content_size;
+                container.context_menu_body(|menu| {
+                    if menu.menu_item("Clear Log") {
+                        self.logbuf.clear();
+                    }
+                });
                 container.set_row_widths_height(&[-1], -1);
 
                 container.text(self.logbuf.as_str());
@@ -321,67 +587,52 @@ impl<'a> State<'a> {
             }
         });
     }
-    fn uint8_slider(&mut self, value: &mut u8, low: i32, high: i32, ctx: &mut microui_redux::Container<()>) -> ResourceState {
-        let mut tmp = *value as f32;
-        ctx.idmngr.push_id_from_ptr(value);
-        let res = ctx.slider_ex(&mut tmp, low as Real, high as Real, 0 as Real, 0, WidgetOption::ALIGN_CENTER);
-        *value = tmp as u8;
-        ctx.idmngr.pop_id();
-        return res;
-    }
     fn style_window(&mut self, ctx: &mut Context) {
         ctx.window(&mut self.style_window.as_mut().unwrap().clone(), WidgetOption::NONE, |container| {
             let sw = (container.body.width as f64 * 0.14) as i32;
-            container.set_row_widths_height(&[80, sw, sw, sw, sw, -1], 0);
+            container.set_row_widths_height(&[80, -1], 0);
             let mut i = 0;
             while self.label_colors[i].label.len() > 0 {
                 container.label(self.label_colors[i].label);
-                unsafe {
-                    let color = self.style.colors.as_mut_ptr().offset(i as isize);
-                    self.uint8_slider(&mut (*color).r, 0, 255, container);
-                    self.uint8_slider(&mut (*color).g, 0, 255, container);
-                    self.uint8_slider(&mut (*color).b, 0, 255, container);
-                    self.uint8_slider(&mut (*color).a, 0, 255, container);
-                }
-                let next_layout = container.next_cell();
-                let color = self.style.colors[i];
-                container.draw_rect(next_layout, color);
+                container.color_button(self.label_colors[i].label, &mut self.style.colors[i]);
                 i += 1;
             }
             container.set_row_widths_height(&[80, sw], 0);
             container.label("padding");
-            let mut tmp = self.style.padding as u8;
-            self.uint8_slider(&mut tmp, 0, 16, container);
-            self.style.padding = tmp as i32;
+            container.slider_ex(&mut self.style.padding, 0, 16, 0, 0, WidgetOption::ALIGN_CENTER);
 
             container.label("spacing");
-            let mut tmp = self.style.spacing as u8;
-            self.uint8_slider(&mut tmp, 0, 16, container);
-            self.style.spacing = tmp as i32;
+            container.slider_ex(&mut self.style.spacing, 0, 16, 0, 0, WidgetOption::ALIGN_CENTER);
 
             container.label("title height");
-            let mut tmp = self.style.title_height as u8;
-            self.uint8_slider(&mut tmp, 0, 128, container);
-            self.style.title_height = tmp as i32;
+            container.slider_ex(&mut self.style.title_height, 0, 128, 0, 0, WidgetOption::ALIGN_CENTER);
 
             container.label("thumb size");
-            let mut tmp = self.style.thumb_size as u8;
-            self.uint8_slider(&mut tmp, 0, 128, container);
-            self.style.thumb_size = tmp as i32;
+            container.slider_ex(&mut self.style.thumb_size, 0, 128, 0, 0, WidgetOption::ALIGN_CENTER);
 
             container.label("scroll size");
-            let mut tmp = self.style.scrollbar_size as u8;
-            self.uint8_slider(&mut tmp, 0, 128, container);
-            self.style.scrollbar_size = tmp as i32;
+            container.slider_ex(&mut self.style.scrollbar_size, 0, 128, 0, 0, WidgetOption::ALIGN_CENTER);
         });
         ctx.set_style(&self.style);
     }
 
     fn process_frame(&mut self, ctx: &mut Context) {
         ctx.frame(|ctx| {
+            if ctx.is_shortcut_fired("open_popup") {
+                self.open_popup = true;
+            }
             self.style_window(ctx);
             self.log_window(ctx);
             self.test_window(ctx);
+
+            let mut confirm_window = self.confirm_window.clone().unwrap();
+            if self.confirm_clear {
+                ctx.open_modal(&mut confirm_window);
+                self.confirm_clear = false;
+            }
+            if ctx.confirm_dialog(&mut confirm_window, "Clear the whole log?") == Some(DialogResult::Ok) {
+                self.logbuf.clear();
+            }
         })
     }
 }
@@ -402,7 +653,13 @@ fn main() {
         state.log_window = Some(ctx.new_window("Log Window", rect(350, 40, 300, 200)));
         state.style_window = Some(ctx.new_window("Style Editor", rect(350, 250, 300, 240)));
         state.popup_window = Some(ctx.new_popup("Test Popup"));
+        state.confirm_window = Some(ctx.new_modal("Confirm", rect(0, 0, 220, 90)));
         state.log_output = Some(ctx.new_panel("Log Outputman, "));
+        state.rows_panel = Some(ctx.new_panel("Rows"));
+        state.files_panel = Some(ctx.new_panel("Files"));
+        ctx.register_shortcut("clear_log", Shortcut::new(KeyMode::CTRL, Key::L)).unwrap();
+        ctx.register_shortcut("open_popup", Shortcut::new(KeyMode::CTRL | KeyMode::SHIFT, Key::P))
+            .unwrap();
         state
     })
     .unwrap();
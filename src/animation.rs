@@ -0,0 +1,232 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+use super::*;
+use std::collections::HashMap;
+
+// durations of the built-in animations, in milliseconds
+pub(crate) const HOVER_FADE_MS: u64 = 100;
+pub(crate) const NODE_SLIDE_MS: u64 = 150;
+pub(crate) const SMOOTH_SCROLL_MS: u64 = 120;
+pub(crate) const TOGGLE_SLIDE_MS: u64 = 120;
+pub(crate) const CARET_BLINK_MS: u64 = 530;
+pub(crate) const BUSY_SWEEP_MS: u64 = 1200;
+pub(crate) const SPINNER_TURN_MS: u64 = 800;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    // maps the animation progress in [0, 1] onto the curve
+    pub fn apply(&self, t: Real) -> Real {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    -1.0 + (4.0 - 2.0 * t) * t
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Tween {
+    from: Real,
+    to: Real,
+    start: u64,
+    duration: u64,
+    easing: Easing,
+    touched: bool,
+}
+
+impl Tween {
+    fn settled(value: Real, time: u64) -> Self {
+        Self {
+            from: value,
+            to: value,
+            start: time,
+            duration: 0,
+            easing: Easing::Linear,
+            touched: true,
+        }
+    }
+
+    fn is_running(&self, time: u64) -> bool {
+        time.saturating_sub(self.start) < self.duration
+    }
+
+    fn value(&self, time: u64) -> Real {
+        if !self.is_running(time) {
+            return self.to;
+        }
+        let t = time.saturating_sub(self.start) as Real / self.duration as Real;
+        self.from + (self.to - self.from) * self.easing.apply(t)
+    }
+}
+
+// values animated over the frame clock, keyed by the id of the widget owning them. Entries that are not used during a
+// frame are dropped at the start of the next one.
+#[derive(Clone, Default)]
+pub(crate) struct Animations {
+    tweens: HashMap<Id, Tween>,
+}
+
+impl Animations {
+    // moves the value toward `to`, restarting from the current value whenever the target changes. A new entry starts
+    // at `from`. Hosts that don't drive the frame clock keep the time at 0 and get the value snapped to the target.
+    pub(crate) fn tween(&mut self, id: Id, from: Real, to: Real, duration: u64, easing: Easing, time: u64) -> Real {
+        if time == 0 {
+            self.tweens.insert(id, Tween::settled(to, time));
+            return to;
+        }
+        let tween = self.tweens.entry(id).or_insert_with(|| Tween::settled(from, time));
+        tween.touched = true;
+        if tween.to != to {
+            *tween = Tween {
+                from: tween.value(time),
+                to,
+                start: time,
+                duration,
+                easing,
+                touched: true,
+            };
+        }
+        tween.value(time)
+    }
+
+    pub(crate) fn target(&self, id: Id) -> Option<Real> {
+        self.tweens.get(&id).map(|t| t.to)
+    }
+
+    pub(crate) fn is_running(&self, id: Id, time: u64) -> bool {
+        self.tweens.get(&id).is_some_and(|t| t.is_running(time))
+    }
+
+    // keeps a value around without animating it
+    pub(crate) fn set(&mut self, id: Id, value: Real, time: u64) {
+        self.tweens.insert(id, Tween::settled(value, time));
+    }
+
+    // milliseconds since the entry was created or last restarted
+    pub(crate) fn elapsed(&mut self, id: Id, restart: bool, time: u64) -> u64 {
+        let tween = self.tweens.entry(id).or_insert_with(|| Tween::settled(0.0, time));
+        if restart {
+            tween.start = time;
+        }
+        tween.touched = true;
+        time.saturating_sub(tween.start)
+    }
+
+    pub(crate) fn remove(&mut self, id: Id) {
+        self.tweens.remove(&id);
+    }
+
+    pub(crate) fn prune(&mut self) {
+        self.tweens.retain(|_, t| t.touched);
+        for t in self.tweens.values_mut() {
+            t.touched = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(name: &str) -> Id {
+        IdManager::new().get_id_from_str(name)
+    }
+
+    #[test]
+    fn easing_ends() {
+        for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!((easing.apply(-1.0), easing.apply(0.0)), (0.0, 0.0));
+            assert_eq!((easing.apply(1.0), easing.apply(2.0)), (1.0, 1.0));
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert!(Easing::EaseIn.apply(0.5) < 0.5 && Easing::EaseOut.apply(0.5) > 0.5);
+    }
+
+    #[test]
+    fn tween_moves_to_the_target() {
+        let mut anims = Animations::default();
+        let a = id("a");
+        assert_eq!(anims.tween(a, 0.0, 0.0, 100, Easing::Linear, 1000), 0.0);
+        assert_eq!(anims.tween(a, 0.0, 10.0, 100, Easing::Linear, 1000), 0.0);
+        assert_eq!(anims.tween(a, 0.0, 10.0, 100, Easing::Linear, 1050), 5.0);
+        assert!(anims.is_running(a, 1050));
+        assert_eq!(anims.tween(a, 0.0, 10.0, 100, Easing::Linear, 1100), 10.0);
+        assert!(!anims.is_running(a, 1100));
+        assert_eq!(anims.target(a), Some(10.0));
+    }
+
+    #[test]
+    fn tween_restarts_from_where_it_is() {
+        let mut anims = Animations::default();
+        let a = id("a");
+        anims.tween(a, 0.0, 10.0, 100, Easing::Linear, 1000);
+        assert_eq!(anims.tween(a, 0.0, 10.0, 100, Easing::Linear, 1050), 5.0);
+        // turning back halfway goes from 5 to 0 over a full duration
+        assert_eq!(anims.tween(a, 0.0, 0.0, 100, Easing::Linear, 1050), 5.0);
+        assert_eq!(anims.tween(a, 0.0, 0.0, 100, Easing::Linear, 1100), 2.5);
+        assert_eq!(anims.tween(a, 0.0, 0.0, 100, Easing::Linear, 1150), 0.0);
+    }
+
+    #[test]
+    fn no_clock_snaps_to_the_target() {
+        let mut anims = Animations::default();
+        let a = id("a");
+        assert_eq!(anims.tween(a, 0.0, 10.0, 100, Easing::Linear, 0), 10.0);
+        assert!(!anims.is_running(a, 0));
+    }
+
+    #[test]
+    fn prune_drops_untouched_entries() {
+        let mut anims = Animations::default();
+        let (a, b) = (id("a"), id("b"));
+        anims.tween(a, 0.0, 1.0, 100, Easing::Linear, 1000);
+        anims.set(b, 3.0, 1000);
+        anims.prune();
+        assert_eq!((anims.target(a), anims.target(b)), (Some(1.0), Some(3.0)));
+        anims.tween(a, 0.0, 1.0, 100, Easing::Linear, 1016);
+        anims.prune();
+        assert_eq!((anims.target(a), anims.target(b)), (Some(1.0), None));
+    }
+}
@@ -0,0 +1,425 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+use super::*;
+use std::collections::HashMap;
+
+// side of the saturation/value square and width of the hue and alpha strips next to it
+const SQUARE_SIZE: i32 = 128;
+const STRIP_WIDTH: i32 = 16;
+// the square is drawn as a grid of cells of this many pixels, the strips as bands of this many pixels
+const SQUARE_CELL: i32 = 8;
+const STRIP_BAND: i32 = 4;
+const CHECKER_CELL: i32 = 4;
+
+// the color of the given hue, saturation and value, all in [0, 1]
+pub fn hsv_color(hue: Real, saturation: Real, value: Real, alpha: u8) -> Color {
+    let h = hue.rem_euclid(1.0) * 6.0;
+    let c = value * saturation;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as i32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = value - c;
+    let channel = |v: Real| ((v + m).clamp(0.0, 1.0) * 255.0).round() as u8;
+    color(channel(r), channel(g), channel(b), alpha)
+}
+
+// the hue, saturation and value of the color, all in [0, 1]. A gray has a hue of 0
+pub fn color_to_hsv(c: Color) -> (Real, Real, Real) {
+    let (r, g, b) = (c.r as Real / 255.0, c.g as Real / 255.0, c.b as Real / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    (hue / 6.0, saturation, max)
+}
+
+fn hex_string(c: Color) -> String {
+    if c.a == 255 {
+        format!("{:02X}{:02X}{:02X}", c.r, c.g, c.b)
+    } else {
+        format!("{:02X}{:02X}{:02X}{:02X}", c.r, c.g, c.b, c.a)
+    }
+}
+
+// RRGGBB keeps the given alpha, RRGGBBAA replaces it
+fn parse_hex(text: &str, alpha: u8) -> Option<Color> {
+    let v = u32::from_str_radix(text, 16).ok()?;
+    match text.len() {
+        6 => Some(color((v >> 16) as u8, (v >> 8) as u8, v as u8, alpha)),
+        8 => Some(color((v >> 24) as u8, (v >> 16) as u8, (v >> 8) as u8, v as u8)),
+        _ => None,
+    }
+}
+
+// what a color picker remembers between frames, kept by its container under the picker id
+#[derive(Clone, Default)]
+pub(crate) struct ColorPickerState {
+    hue: Real,
+    saturation: Real,
+    value: Real,
+    // the color the fields show, the caller setting another one resyncs them
+    color: Option<Color>,
+    channels: [u8; 4],
+    hex: String,
+    touched: bool,
+}
+
+impl ColorPickerState {
+    fn sync(&mut self, c: Color) {
+        let (hue, saturation, value) = color_to_hsv(c);
+        // a gray has no hue and black no saturation, the ones picked last stay
+        if value > 0.0 {
+            if saturation > 0.0 {
+                self.hue = hue;
+            }
+            self.saturation = saturation;
+        }
+        self.value = value;
+        self.set_color(c);
+    }
+
+    fn set_color(&mut self, c: Color) {
+        self.color = Some(c);
+        self.channels = [c.r, c.g, c.b, c.a];
+    }
+}
+
+// color pickers of a container, the ones not drawn during a frame are forgotten at the start of the next one
+#[derive(Clone, Default)]
+pub(crate) struct ColorPickers {
+    pickers: HashMap<Id, ColorPickerState>,
+}
+
+impl ColorPickers {
+    fn take(&mut self, id: Id) -> ColorPickerState {
+        self.pickers.remove(&id).unwrap_or_default()
+    }
+
+    fn put(&mut self, id: Id, mut state: ColorPickerState) {
+        state.touched = true;
+        self.pickers.insert(id, state);
+    }
+
+    pub(crate) fn prune(&mut self) {
+        self.pickers.retain(|_, s| s.touched);
+        for s in self.pickers.values_mut() {
+            s.touched = false;
+        }
+    }
+}
+
+impl<PR: Clone> Container<PR> {
+    // a saturation/value square with hue and alpha strips beside it, a swatch with the hex code and the RGBA channels
+    // below. The hex code is applied on return, the channels are dragged or shift clicked like number fields.
+    // `name` keys the state of the picker, like the hue of a gray or the hex code being typed. Returns CHANGE when the
+    // color was edited.
+    pub fn color_picker(&mut self, name: &str, value: &mut Color) -> ResourceState {
+        let mut res = ResourceState::NONE;
+        let id = self.idmngr.get_id_from_str(name);
+        let mut state = self.pickers.take(id);
+        if state.color != Some(*value) {
+            state.sync(*value);
+        }
+        let spacing = self.style.spacing;
+        let width = SQUARE_SIZE + (STRIP_WIDTH + spacing) * 2;
+        self.layout.row(&[width], SQUARE_SIZE);
+        let r = self.layout.next();
+        let square = rect(r.x, r.y, SQUARE_SIZE, r.height);
+        let hue_strip = rect(square.x + square.width + spacing, r.y, STRIP_WIDTH, r.height);
+        let alpha_strip = rect(hue_strip.x + hue_strip.width + spacing, r.y, STRIP_WIDTH, r.height);
+
+        let mut dragged = false;
+        let mut alpha = value.a;
+        if let Some((x, y)) = self.picker_drag(IdManager::get_sub_id(id, "!sv"), square) {
+            state.saturation = x;
+            state.value = 1.0 - y;
+            dragged = true;
+        }
+        if let Some((_, y)) = self.picker_drag(IdManager::get_sub_id(id, "!hue"), hue_strip) {
+            state.hue = y;
+            dragged = true;
+        }
+        if let Some((_, y)) = self.picker_drag(IdManager::get_sub_id(id, "!alpha"), alpha_strip) {
+            alpha = ((1.0 - y) * 255.0).round() as u8;
+            dragged = true;
+        }
+        let mut picked = None;
+        if dragged {
+            let c = hsv_color(state.hue, state.saturation, state.value, alpha);
+            state.set_color(c);
+            picked = Some(c);
+        }
+        let current = state.color.unwrap_or(*value);
+        self.draw_color_square(&state, square);
+        self.draw_hue_strip(&state, hue_strip);
+        self.draw_alpha_strip(current, alpha_strip);
+
+        let swatch_width = SQUARE_SIZE / 4;
+        self.layout.row(&[swatch_width, width - swatch_width - spacing], 0);
+        let swatch = self.layout.next();
+        self.draw_swatch(current, swatch);
+        let hex_id = IdManager::get_sub_id(id, "!hex");
+        let hex_rect = self.layout.next();
+        // the hex code follows the color unless it is being typed
+        if self.focus != Some(hex_id) {
+            state.hex = hex_string(current);
+        }
+        let field = TextField {
+            filter: TextFilter::Hex,
+            max_length: Some(8),
+            validate: Some(&|text: &str| text.len() == 6 || text.len() == 8),
+        };
+        if self.textbox_field(&mut state.hex, hex_id, hex_rect, &field, WidgetOption::NONE).is_submitted() {
+            if let Some(c) = parse_hex(state.hex.as_str(), current.a) {
+                state.sync(c);
+                picked = Some(c);
+            }
+        }
+
+        let channel_width = (width - spacing * 3) / 4;
+        self.layout.row(&[channel_width; 4], 0);
+        for (i, name) in ["R", "G", "B", "A"].iter().enumerate() {
+            let r = self.layout.next();
+            let channel_id = IdManager::get_sub_id(id, name);
+            if self.number_raw(&mut state.channels[i], channel_id, r, 1.0, 0, WidgetOption::NONE).is_changed() {
+                let [r, g, b, a] = state.channels;
+                let c = color(r, g, b, a);
+                state.sync(c);
+                picked = Some(c);
+            }
+            self.draw_field_hint(name, r);
+        }
+        self.pickers.put(id, state);
+
+        if let Some(c) = picked.filter(|c| c != value) {
+            *value = c;
+            res |= ResourceState::CHANGE;
+        }
+        res
+    }
+
+    // a swatch of the color, clicking it opens a color picker below it. `name` keys the button and its picker, it isn't
+    // drawn. Returns CHANGE when the color was edited
+    pub fn color_button(&mut self, name: &str, value: &mut Color) -> ResourceState {
+        let mut res = ResourceState::NONE;
+        let id = self.idmngr.get_id_from_str(name);
+        let owner = (self as *const Self as usize, Some(IdManager::get_sub_id(id, "!picker")));
+        let open = self.shared.menu.borrow().is_open_for(owner);
+        let r = self.layout.next();
+        self.update_control(id, r, WidgetOption::NONE);
+        if self.is_activated(id) {
+            if open {
+                self.shared.menu.borrow_mut().close();
+            } else {
+                self.shared.menu.borrow_mut().request(owner, r);
+            }
+        }
+        self.draw_control_frame(id, r, ControlColor::Button, WidgetOption::NONE);
+        self.draw_swatch(*value, expand_rect(r, -3));
+        if !self.shared.menu.borrow().is_open_for(owner) {
+            return res;
+        }
+        let (mut window, screen) = self.menu_window(0);
+        window.pulldown(r, screen, |c| {
+            res |= c.color_picker(name, value);
+        });
+        res
+    }
+
+    // the mouse position inside the rectangle as fractions of its size, while it is pressed or dragged there
+    fn picker_drag(&mut self, id: Id, r: Recti) -> Option<(Real, Real)> {
+        self.update_control(id, r, WidgetOption::NONE);
+        let input = self.input.borrow();
+        if self.focus != Some(id) || (input.mouse_down.is_none() && !input.mouse_pressed.is_left()) {
+            return None;
+        }
+        let x = (input.mouse_pos.x - r.x) as Real / r.width as Real;
+        let y = (input.mouse_pos.y - r.y) as Real / r.height as Real;
+        Some((x.clamp(0.0, 1.0), y.clamp(0.0, 1.0)))
+    }
+
+    fn draw_checker(&mut self, r: Recti) {
+        self.draw_rect(r, color(204, 204, 204, 255));
+        for y in (0..r.height).step_by(CHECKER_CELL as usize) {
+            for x in (0..r.width).step_by(CHECKER_CELL as usize) {
+                if (x + y) / CHECKER_CELL % 2 == 1 {
+                    let cell = rect(r.x + x, r.y + y, CHECKER_CELL.min(r.width - x), CHECKER_CELL.min(r.height - y));
+                    self.draw_rect(cell, color(153, 153, 153, 255));
+                }
+            }
+        }
+    }
+
+    // the color over a checkerboard showing through its transparency
+    fn draw_swatch(&mut self, c: Color, r: Recti) {
+        self.draw_checker(r);
+        self.draw_rect(r, c);
+    }
+
+    fn draw_color_square(&mut self, state: &ColorPickerState, r: Recti) {
+        let cells = r.width / SQUARE_CELL;
+        for j in 0..cells {
+            for i in 0..cells {
+                let saturation = (i as Real + 0.5) / cells as Real;
+                let value = 1.0 - (j as Real + 0.5) / cells as Real;
+                let cell = rect(r.x + i * SQUARE_CELL, r.y + j * SQUARE_CELL, SQUARE_CELL, SQUARE_CELL);
+                self.draw_rect(cell, hsv_color(state.hue, saturation, value, 255));
+            }
+        }
+        let x = r.x + (state.saturation * r.width as Real).round() as i32;
+        let y = r.y + ((1.0 - state.value) * r.height as Real).round() as i32;
+        // dark marker over the bright half, light one over the dark half
+        let marker = if state.value > 0.5 { color(0, 0, 0, 255) } else { color(255, 255, 255, 255) };
+        self.draw_box(rect(x - 3, y - 3, 7, 7), marker);
+    }
+
+    fn draw_hue_strip(&mut self, state: &ColorPickerState, r: Recti) {
+        let bands = r.height / STRIP_BAND;
+        for j in 0..bands {
+            let hue = (j as Real + 0.5) / bands as Real;
+            self.draw_rect(rect(r.x, r.y + j * STRIP_BAND, r.width, STRIP_BAND), hsv_color(hue, 1.0, 1.0, 255));
+        }
+        self.draw_strip_marker(r, state.hue);
+    }
+
+    fn draw_alpha_strip(&mut self, c: Color, r: Recti) {
+        self.draw_checker(r);
+        let bands = r.height / STRIP_BAND;
+        for j in 0..bands {
+            let alpha = 1.0 - (j as Real + 0.5) / bands as Real;
+            let band = color(c.r, c.g, c.b, (alpha * 255.0).round() as u8);
+            self.draw_rect(rect(r.x, r.y + j * STRIP_BAND, r.width, STRIP_BAND), band);
+        }
+        self.draw_strip_marker(r, 1.0 - c.a as Real / 255.0);
+    }
+
+    fn draw_strip_marker(&mut self, r: Recti, at: Real) {
+        let y = r.y + (at * r.height as Real).round() as i32;
+        let color = self.style.colors[ControlColor::Text as usize];
+        self.draw_box(rect(r.x - 1, y - 2, r.width + 2, 5), color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgba(c: Color) -> (u8, u8, u8, u8) {
+        (c.r, c.g, c.b, c.a)
+    }
+
+    #[test]
+    fn hsv_round_trip() {
+        for r in (0..=255).step_by(15) {
+            for g in (0..=255).step_by(15) {
+                for b in (0..=255).step_by(15) {
+                    let c = color(r, g, b, 200);
+                    let (h, s, v) = color_to_hsv(c);
+                    assert!((0.0..1.0).contains(&h) && (0.0..=1.0).contains(&s) && (0.0..=1.0).contains(&v));
+                    assert_eq!(rgba(hsv_color(h, s, v, 200)), rgba(c), "{:?}", (h, s, v));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hsv_primaries() {
+        assert_eq!(rgba(hsv_color(0.0, 1.0, 1.0, 255)), rgba(color(255, 0, 0, 255)));
+        assert_eq!(rgba(hsv_color(1.0 / 3.0, 1.0, 1.0, 255)), rgba(color(0, 255, 0, 255)));
+        assert_eq!(rgba(hsv_color(2.0 / 3.0, 1.0, 1.0, 255)), rgba(color(0, 0, 255, 255)));
+        // the hue wraps around
+        assert_eq!(rgba(hsv_color(1.0, 1.0, 1.0, 255)), rgba(color(255, 0, 0, 255)));
+        assert_eq!(color_to_hsv(color(128, 128, 128, 255)), (0.0, 0.0, 128.0 / 255.0));
+    }
+
+    #[cfg(feature = "builder")]
+    #[test]
+    fn picker_keeps_the_hue_of_a_moving_color() {
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 10, 300, 300));
+        // the color lives in a new box every frame, the old ones stay alive so the address always changes
+        let mut boxes = vec![Box::new(color(0, 255, 0, 255))];
+        let mut hues = Vec::new();
+        for step in 0..3 {
+            let mut value = Box::new(if step == 0 { **boxes.last().unwrap() } else { color(128, 128, 128, 255) });
+            ctx.frame(|ctx| {
+                ctx.window(&mut window, WidgetOption::NONE, |c| {
+                    c.color_picker("tint", &mut value);
+                    let id = c.idmngr.get_id_from_str("tint");
+                    hues.push(c.pickers.pickers[&id].hue);
+                });
+            });
+            boxes.push(value);
+        }
+        // the gray has no hue of its own, the green one stays
+        assert!(hues.iter().all(|h| (h - 1.0 / 3.0).abs() < 1e-5), "{:?}", hues);
+    }
+
+    #[cfg(feature = "builder")]
+    #[test]
+    fn popup_stays_open_for_a_moving_color() {
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 10, 300, 300));
+        let mut boxes = vec![Box::new(color(0, 255, 0, 255))];
+        let mut open = Vec::new();
+        ctx.input.borrow_mut().mousemove(40, 39);
+        for step in 0..6 {
+            match step {
+                2 => ctx.input.borrow_mut().mousedown(40, 39, MouseButton::LEFT),
+                3 => ctx.input.borrow_mut().mouseup(40, 39, MouseButton::LEFT),
+                _ => {}
+            }
+            let mut value = Box::new(**boxes.last().unwrap());
+            ctx.frame(|ctx| {
+                ctx.window(&mut window, WidgetOption::NONE, |c| {
+                    c.set_row_widths_height(&[60], 0);
+                    c.color_button("tint", &mut value);
+                });
+            });
+            open.push(ctx.shared.menu.borrow().owner().is_some());
+            boxes.push(value);
+        }
+        assert_eq!(open, vec![false, false, true, true, true, true]);
+    }
+}
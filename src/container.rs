@@ -51,6 +51,7 @@
 // IN THE SOFTWARE.
 //
 use super::*;
+use std::any::Any;
 use std::cell::RefCell;
 
 #[derive(Clone)]
@@ -94,6 +95,91 @@ impl<PR> Default for Command<PR> {
     }
 }
 
+// keyboard focus of a window: interactive widgets are numbered in submission order, panels share their window's state
+#[derive(Clone, Default)]
+pub(crate) struct Navigation {
+    pub(crate) active: bool,
+    focus: Option<usize>,
+    visible: bool,
+    editing: bool,
+    rects: Vec<Recti>,
+}
+
+impl Navigation {
+    pub(crate) fn reset(&mut self) {
+        self.rects.clear();
+        self.editing = false;
+    }
+
+    fn register(&mut self, rect: Recti) -> usize {
+        self.rects.push(rect);
+        self.rects.len() - 1
+    }
+
+    // nearest widget whose center lies in the direction (dx, dy) from the focused one, off-axis distance weighs double
+    fn nearest(&self, from: usize, dx: i32, dy: i32) -> Option<usize> {
+        let center = |r: &Recti| vec2(r.x + r.width / 2, r.y + r.height / 2);
+        let c = center(&self.rects[from]);
+        let mut best: Option<(i32, usize)> = None;
+        for (i, r) in self.rects.iter().enumerate() {
+            let p = center(r);
+            let along = (p.x - c.x) * dx + (p.y - c.y) * dy;
+            let across = ((p.x - c.x) * dy).abs() + ((p.y - c.y) * dx).abs();
+            if i == from || along <= 0 {
+                continue;
+            }
+            let score = along + across * 2;
+            if best.map_or(true, |(s, _)| score < s) {
+                best = Some((score, i));
+            }
+        }
+        best.map(|(_, i)| i)
+    }
+
+    pub(crate) fn navigate(&mut self, input: &Input) {
+        let gamepad = input.gamepad_pressed();
+        if input.is_key_pressed(Key::Escape) || gamepad.is_cancel() {
+            self.focus = None;
+        }
+
+        let count = self.rects.len();
+        if count == 0 {
+            return;
+        }
+        if input.is_key_pressed(Key::Tab) {
+            let back = input.is_key_down(Key::Shift);
+            self.focus = Some(match self.focus {
+                Some(i) if back => (min(i, count) + count - 1) % count,
+                Some(i) => (i + 1) % count,
+                None if back => count - 1,
+                None => 0,
+            });
+            self.visible = true;
+        }
+
+        // arrows stay with the text field being edited, the d-pad always navigates
+        let arrow = |key| !self.editing && input.is_key_pressed(key);
+        let direction = if gamepad.is_left() || arrow(Key::Left) {
+            Some((-1, 0))
+        } else if gamepad.is_right() || arrow(Key::Right) {
+            Some((1, 0))
+        } else if gamepad.is_up() || arrow(Key::Up) {
+            Some((0, -1))
+        } else if gamepad.is_down() || arrow(Key::Down) {
+            Some((0, 1))
+        } else {
+            None
+        };
+        if let Some((dx, dy)) = direction {
+            self.focus = match self.focus {
+                Some(i) if i < count => self.nearest(i, dx, dy).or(Some(i)),
+                _ => Some(0),
+            };
+            self.visible = true;
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Container<PR> {
     pub(crate) atlas: AtlasHandle,
@@ -116,11 +202,29 @@ pub struct Container<PR> {
     pub number_edit_buf: String,
     pub number_edit: Option<Id>,
 
+    caret_rect: Option<Recti>,
+    cursor: Option<CursorIcon>,
+    pub(crate) last_control: Option<(Id, Recti)>,
+    // the strip below the title of a window opened with MENU_BAR
+    pub(crate) menu_bar: Option<Recti>,
+    pub(crate) shared: Shared<PR>,
+    anims: Animations,
+    // set while the widgets are only drawn, like the content of a closing header or a window below an open modal
+    no_interact: bool,
+    pub(crate) tabs: TabBars,
+    pub(crate) pickers: ColorPickers,
+    // the axes, x then y, the wheel can move this frame
+    scrollable: [Option<Scrollable>; 2],
+    // the number field being dragged and the part of the drag its value couldn't take yet, like a fraction of an integer
+    number_drag: Option<(Id, f64)>,
+    pub(crate) nav: Rc<RefCell<Navigation>>,
+    nav_rect: Option<Recti>,
+    activated: Option<Id>,
     panels: Vec<ContainerHandle<PR>>,
 }
 
 impl<PR: Clone> Container<PR> {
-    pub(crate) fn new(name: &str, atlas: AtlasHandle, style: &Style, input: Rc<RefCell<Input>>) -> Self {
+    pub(crate) fn new(name: &str, atlas: AtlasHandle, style: &Style, input: Rc<RefCell<Input>>, shared: Shared<PR>) -> Self {
         Self {
             name: name.to_string(),
             style: style.clone(),
@@ -142,6 +246,20 @@ impl<PR: Clone> Container<PR> {
             in_hover_root: false,
             input: input,
 
+            caret_rect: None,
+            cursor: None,
+            last_control: None,
+            menu_bar: None,
+            shared,
+            anims: Animations::default(),
+            no_interact: false,
+            tabs: TabBars::default(),
+            pickers: ColorPickers::default(),
+            scrollable: [None; 2],
+            number_drag: None,
+            nav: Rc::new(RefCell::new(Navigation::default())),
+            nav_rect: None,
+            activated: None,
             panels: Default::default(),
         }
     }
@@ -149,9 +267,69 @@ impl<PR: Clone> Container<PR> {
     pub(crate) fn prepare(&mut self) {
         self.command_list.clear();
         assert!(self.clip_stack.len() == 0);
+        self.caret_rect = None;
+        self.cursor = None;
+        self.anims.prune();
+        self.tabs.prune();
+        self.pickers.prune();
         self.panels.clear();
     }
 
+    // eases the value owned by `id` toward `target` over `duration_ms`, restarting from its current value when the
+    // target changes. The value appears at the target the first time it is animated.
+    pub fn animate(&mut self, id: Id, target: Real, duration_ms: u64, easing: Easing) -> Real {
+        let time = self.input.borrow().time();
+        self.anims.tween(id, target, target, duration_ms, easing, time)
+    }
+
+    pub(crate) fn begin_root(&mut self) {
+        self.clip_stack.push(UNCLIPPED_RECT);
+    }
+
+    pub(crate) fn end_root(&mut self) {
+        self.pop_clip_rect();
+
+        // a body without any widget has no content
+        let layout = *self.layout.top();
+        self.content_size.x = layout.max.x.saturating_sub(layout.body.x).max(0);
+        self.content_size.y = layout.max.y.saturating_sub(layout.body.y).max(0);
+        self.layout.stack.pop();
+    }
+
+    pub fn shortcut(&self, action: &str) -> Option<Shortcut> {
+        self.shared.shortcuts.borrow().shortcut(action)
+    }
+
+    // the chord registered for the action was pressed this frame, a window below an open modal never sees it
+    pub fn is_shortcut_fired(&self, action: &str) -> bool {
+        !self.no_interact && self.shared.shortcuts.borrow().is_fired(action)
+    }
+
+    pub(crate) fn set_blocked(&mut self, blocked: bool) {
+        self.no_interact = blocked;
+    }
+
+    pub fn set_cursor(&mut self, icon: CursorIcon) {
+        self.cursor = Some(icon);
+    }
+
+    // cursor requested in this container or its panels, if any
+    pub fn cursor(&self) -> Option<CursorIcon> {
+        self.cursor.or_else(|| self.panels.iter().find_map(|p| p.inner().cursor()))
+    }
+
+    // requests the cursor while the control is hovered or dragged
+    pub fn set_control_cursor(&mut self, id: Id, icon: CursorIcon) {
+        if self.hover == Some(id) || (self.focus == Some(id) && !self.input.borrow().mouse_down.is_none()) {
+            self.set_cursor(icon);
+        }
+    }
+
+    // caret of the focused text field in this container or its panels, if any
+    pub fn caret_rect(&self) -> Option<Recti> {
+        self.caret_rect.or_else(|| self.panels.iter().find_map(|p| p.inner().caret_rect()))
+    }
+
     #[inline(never)]
     pub(crate) fn render<R: Renderer<PR>>(&self, canvas: &mut Canvas<PR, R>) {
         for command in &self.command_list {
@@ -224,6 +402,14 @@ impl<PR: Clone> Container<PR> {
         self.updated_focus = true;
     }
 
+    // drops the focus of this container and its panels
+    pub(crate) fn blur(&mut self) {
+        self.focus = None;
+        for panel in &mut self.panels {
+            panel.inner_mut().blur();
+        }
+    }
+
     pub fn draw_rect(&mut self, mut rect: Recti, color: Color) {
         rect = rect.intersect(&self.get_clip_rect()).unwrap_or_default();
         if rect.width > 0 && rect.height > 0 {
@@ -340,6 +526,10 @@ impl<PR: Clone> Container<PR> {
 
     pub fn draw_frame(&mut self, rect: Recti, colorid: ControlColor) {
         let color = self.style.colors[colorid as usize];
+        self.draw_frame_color(rect, color, colorid);
+    }
+
+    fn draw_frame_color(&mut self, rect: Recti, color: Color, colorid: ControlColor) {
         self.draw_rect(rect, color);
         if colorid == ControlColor::ScrollBase || colorid == ControlColor::ScrollThumb || colorid == ControlColor::TitleBG {
             return;
@@ -355,12 +545,20 @@ impl<PR: Clone> Container<PR> {
             return;
         }
 
+        // pressing shows at once, hovering fades in and out
+        let time = self.input.borrow().time();
+        let hover_id = IdManager::get_sub_id(id, "!hover");
+        let hovered = if self.hover == Some(id) { 1.0 } else { 0.0 };
+        let t = self.anims.tween(hover_id, 0.0, hovered, HOVER_FADE_MS, Easing::EaseOut, time);
         if self.focus == Some(id) {
-            colorid.focus()
-        } else if self.hover == Some(id) {
-            colorid.hover()
+            colorid.focus();
+            self.draw_frame(rect, colorid);
+            return;
         }
-        self.draw_frame(rect, colorid);
+        let mut hover = colorid;
+        hover.hover();
+        let color = mix_color(self.style.colors[colorid as usize], self.style.colors[hover as usize], t);
+        self.draw_frame_color(rect, color, colorid);
     }
 
     #[inline(never)]
@@ -389,6 +587,66 @@ impl<PR: Clone> Container<PR> {
         rect.contains(&self.input.borrow().mouse_pos) && clip_rect.contains(&self.input.borrow().mouse_pos) && in_hover_root
     }
 
+    // keyboard focus: clicking a widget moves it there, clicking elsewhere drops it, text fields take the input focus
+    // with it and the other widgets are activated with space, return or the gamepad accept button
+    fn update_nav(&mut self, id: Id, rect: Recti, mouseover: bool, opt: WidgetOption) {
+        let mut nav = self.nav.borrow_mut();
+        let index = nav.register(rect);
+        if !self.input.borrow().mouse_pressed.is_none() {
+            if mouseover {
+                nav.focus = Some(index);
+                nav.visible = false;
+            } else if nav.focus == Some(index) {
+                nav.focus = None;
+            }
+        }
+
+        if !nav.active || nav.focus != Some(index) {
+            if opt.is_holding_focus() && self.focus == Some(id) && nav.focus.is_some_and(|f| f != index) {
+                drop(nav);
+                self.set_focus(None);
+            }
+            return;
+        }
+
+        if nav.visible {
+            self.nav_rect = Some(rect);
+        }
+        nav.editing = opt.is_holding_focus();
+        drop(nav);
+        if opt.is_holding_focus() {
+            if self.focus != Some(id) {
+                self.set_focus(Some(id));
+            }
+        } else {
+            let activate = {
+                let input = self.input.borrow();
+                let pressed = |key| input.is_key_pressed(key) && !input.is_key_repeated(key);
+                pressed(Key::Space) || pressed(Key::Return) || input.gamepad_pressed().is_accept()
+            };
+            if activate {
+                self.set_focus(Some(id));
+                self.activated = Some(id);
+            }
+        }
+    }
+
+    // clicked with the left button or activated from the keyboard this frame
+    pub fn is_activated(&self, id: Id) -> bool {
+        self.focus == Some(id) && (self.input.borrow().mouse_pressed.is_left() || self.activated == Some(id))
+    }
+
+    pub fn is_double_clicked(&self, id: Id) -> bool {
+        self.focus == Some(id) && self.input.borrow().is_double_clicked(MouseButton::LEFT)
+    }
+
+    pub(crate) fn draw_nav_ring(&mut self) {
+        if let Some(r) = self.nav_rect.take() {
+            let color = self.style.colors[ControlColor::FocusRing as usize];
+            self.draw_box(expand_rect(r, 1), color);
+        }
+    }
+
     #[inline(never)]
     pub fn update_control(&mut self, id: Id, rect: Recti, opt: WidgetOption) {
         let in_hover_root = self.in_hover_root;
@@ -396,9 +654,13 @@ impl<PR: Clone> Container<PR> {
         if self.focus == Some(id) {
             self.updated_focus = true;
         }
-        if opt.is_not_interactive() {
+        if opt.is_not_interactive() || self.no_interact {
             return;
         }
+        self.last_control = Some((id, rect));
+        if !opt.has_no_nav() {
+            self.update_nav(id, rect, mouseover, opt);
+        }
         if mouseover && self.input.borrow().mouse_down.is_none() {
             self.hover = Some(id);
         }
@@ -406,7 +668,7 @@ impl<PR: Clone> Container<PR> {
             if !self.input.borrow().mouse_pressed.is_none() && !mouseover {
                 self.set_focus(None);
             }
-            if self.input.borrow().mouse_down.is_none() && !opt.is_holding_focus() {
+            if self.input.borrow().mouse_down.is_none() && !opt.is_holding_focus() && self.activated != Some(id) {
                 self.set_focus(None);
             }
         }
@@ -419,22 +681,319 @@ impl<PR: Clone> Container<PR> {
         }
     }
 
+    // turns the previous interactive widget into a drag source: once it is dragged past a few pixels the payload is
+    // created and carried by the mouse until a drop target takes it. Returns true on the frame the drag starts.
+    pub fn drag_source<T: Any, F: FnOnce() -> T>(&mut self, label: &str, payload: F) -> bool {
+        let id = match self.last_control {
+            Some((id, _)) => id,
+            None => return false,
+        };
+        let mouse_pos = self.input.borrow().mouse_pos;
+        if self.focus != Some(id) || !self.input.borrow().mouse_down.is_left() || !self.shared.drag.borrow().can_start(mouse_pos) {
+            return false;
+        }
+        self.shared.drag.borrow_mut().start(label, Box::new(payload()));
+        true
+    }
+
+    // a payload of type T is being dragged
+    pub fn is_dragging<T: Any>(&self) -> bool {
+        self.shared.drag.borrow().holds::<T>()
+    }
+
+    // accepts a payload of type T released over the rectangle, highlighting it while the payload hovers it
+    pub fn drop_target_rect<T: Any>(&mut self, r: Recti) -> Option<T> {
+        let in_hover_root = self.in_hover_root;
+        if !self.is_dragging::<T>() || !self.mouse_over(r, in_hover_root) {
+            return None;
+        }
+        let color = self.style.colors[ControlColor::FocusRing as usize];
+        self.draw_box(expand_rect(r, 1), color);
+        if self.input.borrow().mouse_down.is_left() {
+            return None;
+        }
+        self.shared.drag.borrow_mut().take::<T>()
+    }
+
+    // makes the previous interactive widget a drop target, see `drop_target_rect`
+    pub fn drop_target<T: Any>(&mut self) -> Option<T> {
+        match self.last_control {
+            Some((_, r)) => self.drop_target_rect(r),
+            None => None,
+        }
+    }
+
+    // shows the text next to the mouse once the previous interactive widget has been hovered for the tooltip delay
+    pub fn tooltip(&mut self, text: &str) {
+        self.tooltip_with(|c| {
+            let font = c.style.font;
+            let width = text.lines().map(|line| c.atlas.get_text_size(font, line).width).max().unwrap_or(0);
+            c.layout.row(&[width + c.style.padding * 2], 0);
+            for line in text.lines() {
+                c.label(line);
+            }
+        });
+    }
+
+    // like `tooltip` with arbitrary content, `f` lays out the auto sized tooltip window
+    pub fn tooltip_with<F: FnOnce(&mut Container<PR>)>(&mut self, f: F) {
+        let id = match self.last_control {
+            Some((id, _)) => id,
+            None => return,
+        };
+        if self.hover != Some(id) || !self.input.borrow().mouse_down.is_none() || self.shared.drag.borrow().is_active() {
+            return;
+        }
+        let key = (self as *const Self as usize, id);
+        let time = self.input.borrow().time();
+        let window = self.shared.tooltip.borrow_mut().hover(key, time);
+        if let Some(mut window) = window {
+            let mouse_pos = self.input.borrow().mouse_pos;
+            let screen = self.shared.tooltip.borrow().screen;
+            window.tooltip(mouse_pos, screen, f);
+        }
+    }
+
+    // opens a menu at the mouse when the previous widget is right clicked. `f` lays out the menu with the menu items,
+    // separators and submenus.
+    pub fn context_menu_for_last<F: FnOnce(&mut Container<PR>)>(&mut self, f: F) {
+        let id = match self.last_control {
+            Some((id, _)) => id,
+            None => return,
+        };
+        let clicked = self.input.borrow().mouse_pressed.is_right() && self.hover == Some(id);
+        self.context_menu((self as *const Self as usize, Some(id)), clicked, f);
+    }
+
+    // opens a menu at the mouse when the body of the container is right clicked, wherever it is called from. A widget
+    // with a menu of its own opens that one instead.
+    pub fn context_menu_body<F: FnOnce(&mut Container<PR>)>(&mut self, f: F) {
+        let (body, in_hover_root) = (self.body, self.in_hover_root);
+        let clicked = self.input.borrow().mouse_pressed.is_right() && self.mouse_over(body, in_hover_root);
+        self.context_menu((self as *const Self as usize, None), clicked, f);
+    }
+
+    fn context_menu<F: FnOnce(&mut Container<PR>)>(&mut self, owner: MenuOwner, clicked: bool, f: F) {
+        if clicked {
+            let mouse_pos = self.input.borrow().mouse_pos;
+            self.shared.menu.borrow_mut().request(owner, rect(mouse_pos.x, mouse_pos.y, 0, 0));
+        }
+        let anchor = {
+            let menu = self.shared.menu.borrow();
+            if !menu.is_open_for(owner) {
+                return;
+            }
+            menu.anchor()
+        };
+        let (mut window, screen) = self.menu_window(0);
+        window.menu(anchor, screen, f);
+    }
+
+    // the shared window drawing the given menu level this frame
+    pub(crate) fn menu_window(&mut self, level: usize) -> (WindowHandle<PR>, Dimensioni) {
+        {
+            let mut menu = self.shared.menu.borrow_mut();
+            while menu.windows.len() <= level {
+                let name = format!("!menu{}", menu.windows.len());
+                let window = WindowHandle::popup(&name, self.atlas.clone(), &self.style, self.input.clone(), self.shared.clone());
+                menu.windows.push(window);
+            }
+            let mut window = menu.windows[level].clone();
+            menu.draw_level(level, &mut window);
+            menu.depth = level;
+            (window, menu.screen)
+        }
+    }
+
+    // lays out a full width menu row, the menu is as wide as its widest row measured on the previous draw
+    fn menu_row(&mut self, label: &str, hint_width: i32) -> Recti {
+        let font = self.style.font;
+        let height = self.style.default_cell_size.height + self.style.padding * 2;
+        let width = self.atlas.get_text_size(font, label).width + self.style.padding * 2 + height * 2 + hint_width;
+        self.layout.row(&[max(width, self.content_size.x)], height);
+        self.layout.next()
+    }
+
+    // draws a menu row with room for a check mark on the left and a submenu arrow or the chord of the shortcut on the
+    // right, a NO_INTERACT row is dimmed and can't be picked
+    fn menu_entry(&mut self, label: &str, action: Option<&str>, checked: bool, submenu: bool, opt: WidgetOption) -> (Id, Recti) {
+        let id = self.idmngr.get_id_from_str(label);
+        let shortcut = action.and_then(|action| self.shortcut(action));
+        let hint_width = shortcut.map_or(0, |s| {
+            self.atlas.get_text_size(self.style.font, s.to_string().as_str()).width + self.style.padding * 2
+        });
+        let r = self.menu_row(label, hint_width);
+        self.update_control(id, r, opt);
+        if self.hover == Some(id) || self.focus == Some(id) {
+            self.draw_frame(r, ControlColor::ButtonHover);
+        }
+        let color = if opt.is_not_interactive() {
+            self.dimmed_text_color()
+        } else {
+            self.style.colors[ControlColor::Text as usize]
+        };
+        if checked {
+            self.draw_icon(CHECK_ICON, rect(r.x, r.y, r.height, r.height), color);
+        }
+        if submenu {
+            self.draw_icon(EXPAND_ICON, rect(r.x + r.width - r.height, r.y, r.height, r.height), color);
+        }
+        if let Some(shortcut) = shortcut {
+            self.draw_shortcut_hint(&shortcut, rect(r.x, r.y, r.width - r.height, r.height));
+        }
+        let text = rect(r.x + r.height, r.y, r.width - r.height * 2 - hint_width, r.height);
+        let tsize = self.atlas.get_text_size(self.style.font, label);
+        self.push_clip_rect(text);
+        self.draw_text(
+            self.style.font,
+            label,
+            vec2(text.x + self.style.padding, text.y + (text.height - tsize.height) / 2),
+            color,
+        );
+        self.pop_clip_rect();
+        if self.hover == Some(id) {
+            let mut menu = self.shared.menu.borrow_mut();
+            let level = menu.depth;
+            menu.hover_item(level, if submenu { Some(id) } else { None });
+        }
+        (id, r)
+    }
+
+    // a menu entry, returns true when it is picked, which closes the menu
+    pub fn menu_item(&mut self, label: &str) -> bool {
+        self.menu_item_ex(label, None, WidgetOption::NONE)
+    }
+
+    // a menu entry showing the chord bound to the shortcut action, NO_INTERACT disables it
+    pub fn menu_item_ex(&mut self, label: &str, action: Option<&str>, opt: WidgetOption) -> bool {
+        let (id, _) = self.menu_entry(label, action, false, false, opt);
+        let picked = self.is_activated(id);
+        if picked {
+            self.shared.menu.borrow_mut().close();
+        }
+        picked
+    }
+
+    // a menu entry with a check mark that flips the state when picked
+    pub fn menu_check(&mut self, label: &str, state: &mut bool) -> bool {
+        self.menu_check_ex(label, None, state, WidgetOption::NONE)
+    }
+
+    pub fn menu_check_ex(&mut self, label: &str, action: Option<&str>, state: &mut bool, opt: WidgetOption) -> bool {
+        let (id, _) = self.menu_entry(label, action, *state, false, opt);
+        let picked = self.is_activated(id);
+        if picked {
+            *state = !*state;
+            self.shared.menu.borrow_mut().close();
+        }
+        picked
+    }
+
+    pub fn menu_separator(&mut self) {
+        let width = self.content_size.x;
+        self.layout.row(&[width], 1);
+        let r = self.layout.next();
+        let color = self.style.colors[ControlColor::Button as usize];
+        self.draw_rect(r, color);
+    }
+
+    // an entry opening a nested menu laid out by `f` beside it while hovered
+    pub fn submenu<F: FnOnce(&mut Container<PR>)>(&mut self, label: &str, f: F) {
+        let (id, r) = self.menu_entry(label, None, false, true, WidgetOption::NONE);
+        let level = self.shared.menu.borrow().depth;
+        if !self.shared.menu.borrow().is_submenu_open(level, id) {
+            return;
+        }
+        let padding = self.style.padding;
+        let (mut window, screen) = self.menu_window(level + 1);
+        window.menu(rect(r.x - padding, r.y - padding, r.width + padding * 2, r.height), screen, f);
+        self.shared.menu.borrow_mut().depth = level;
+    }
+
+    // a row of menus opening below their titles when clicked, once one is open hovering another title switches to
+    // it. The bar fills the strip of a window opened with MENU_BAR, or takes a row of the layout.
+    pub fn menu_bar<F: FnOnce(&mut MenuBar<PR>)>(&mut self, f: F) {
+        let (bar, clips) = match self.menu_bar.take() {
+            // the strip is outside of the body the window clips to
+            Some(bar) => (bar, Some(std::mem::take(&mut self.clip_stack))),
+            None => {
+                self.layout.row(&[-1], 0);
+                (self.layout.next(), None)
+            }
+        };
+        let id = self.idmngr.get_id_from_str("!menubar");
+        self.push_clip_rect(bar);
+        let mut menu_bar = MenuBar {
+            container: self,
+            id,
+            bar,
+            x: bar.x,
+            titles: Vec::new(),
+        };
+        f(&mut menu_bar);
+        let titles = menu_bar.titles;
+        self.pop_clip_rect();
+        if let Some(clips) = clips {
+            self.clip_stack = clips;
+        }
+
+        let key = self as *const Self as usize;
+        let open = self.shared.menu.borrow().owner();
+        if let Some((owner, Some(open))) = open {
+            if owner == key && titles.iter().any(|&(t, _)| t == open) {
+                if let Some(&(t, r)) = titles.iter().find(|&&(t, _)| t != open && self.hover == Some(t)) {
+                    self.shared.menu.borrow_mut().request((key, Some(t)), r);
+                }
+            }
+        }
+    }
+
+    fn menu_title<F: FnOnce(&mut Container<PR>)>(&mut self, id: Id, label: &str, r: Recti, f: F) {
+        let owner = (self as *const Self as usize, Some(id));
+        let open = self.shared.menu.borrow().is_open_for(owner);
+        self.update_control(id, r, WidgetOption::NONE);
+        if self.is_activated(id) {
+            if open {
+                self.shared.menu.borrow_mut().close();
+            } else {
+                self.shared.menu.borrow_mut().request(owner, r);
+            }
+        }
+        if open {
+            self.draw_frame(r, ControlColor::ButtonFocus);
+        } else if self.hover == Some(id) {
+            self.draw_frame(r, ControlColor::ButtonHover);
+        }
+        self.draw_control_text(label, r, ControlColor::Text, WidgetOption::ALIGN_CENTER);
+        if !self.shared.menu.borrow().is_open_for(owner) {
+            return;
+        }
+        let (mut window, screen) = self.menu_window(0);
+        window.pulldown(r, screen, f);
+    }
+
     pub fn finish(&mut self) {
         if !self.updated_focus {
             self.focus = None;
         }
         self.updated_focus = false;
+        self.activated = None;
     }
 
     #[inline(never)]
-    fn node(&mut self, label: &str, is_treenode: bool, state: NodeState) -> NodeState {
+    fn node(&mut self, label: &str, is_treenode: bool, state: NodeState, opt: WidgetOption) -> NodeState {
         let id: Id = self.idmngr.get_id_from_str(label);
         self.layout.row(&[-1], 0);
         let mut r = self.layout.next();
-        self.update_control(id, r, WidgetOption::NONE);
+        self.update_control(id, r, opt);
 
         let expanded = state.is_expanded();
-        let active = expanded ^ (self.input.borrow().mouse_pressed.is_left() && self.focus == Some(id));
+        let toggled = if opt.is_double_click() {
+            self.is_double_clicked(id) || self.activated == Some(id)
+        } else {
+            self.is_activated(id)
+        };
+        let active = expanded ^ toggled;
 
         if is_treenode {
             if self.hover == Some(id) {
@@ -453,36 +1012,127 @@ impl<PR: Clone> Container<PR> {
 
     #[must_use]
     pub fn header<F: FnOnce(&mut Self)>(&mut self, label: &str, state: NodeState, f: F) -> NodeState {
-        let new_state = self.node(label, false, state);
-        if new_state.is_expanded() {
-            f(self);
+        self.header_ex(label, state, WidgetOption::NONE, f)
+    }
+
+    // `WidgetOption::DOUBLE_CLICK` toggles the header on double click instead of a single click
+    #[must_use]
+    pub fn header_ex<F: FnOnce(&mut Self)>(&mut self, label: &str, state: NodeState, opt: WidgetOption, f: F) -> NodeState {
+        let new_state = self.node(label, false, state, opt);
+        if let Some(id) = self.idmngr.last_id() {
+            self.node_body(id, new_state.is_expanded(), f);
         }
         new_state
     }
 
     #[must_use]
     pub fn treenode<F: FnOnce(&mut Self)>(&mut self, label: &str, state: NodeState, f: F) -> NodeState {
-        let res = self.node(label, true, state);
-        if res.is_expanded() && self.idmngr.last_id().is_some() {
-            let indent = self.style.indent;
-            self.layout.top_mut().indent += indent;
-            self.idmngr.push_id(self.idmngr.last_id().unwrap());
-        }
+        self.treenode_ex(label, state, WidgetOption::NONE, f)
+    }
 
-        if res.is_expanded() {
-            f(self);
-            let indent = self.style.indent;
-            self.layout.top_mut().indent -= indent;
-            self.idmngr.pop_id();
+    // `WidgetOption::DOUBLE_CLICK` toggles the node on double click instead of a single click
+    #[must_use]
+    pub fn treenode_ex<F: FnOnce(&mut Self)>(&mut self, label: &str, state: NodeState, opt: WidgetOption, f: F) -> NodeState {
+        let res = self.node(label, true, state, opt);
+        if let Some(id) = self.idmngr.last_id() {
+            self.node_body(id, res.is_expanded(), |c| {
+                let indent = c.style.indent;
+                c.layout.top_mut().indent += indent;
+                c.idmngr.push_id(id);
+                f(c);
+                c.layout.top_mut().indent -= indent;
+                c.idmngr.pop_id();
+            });
         }
 
         res
     }
 
+    // lays out the content under a header or tree node, sliding it open or closed. While sliding, the content is
+    // clipped to the height measured on the previous frame and only the visible part takes room in the layout.
+    fn node_body<F: FnOnce(&mut Self)>(&mut self, id: Id, expanded: bool, f: F) {
+        let time = self.input.borrow().time();
+        let open_id = IdManager::get_sub_id(id, "!open");
+        let height_id = IdManager::get_sub_id(id, "!height");
+        let target = if expanded { 1.0 } else { 0.0 };
+        let open = self.anims.tween(open_id, target, target, NODE_SLIDE_MS, Easing::EaseInOut, time);
+        if open <= 0.0 {
+            return;
+        }
+
+        let layout = *self.layout.top();
+        let start = layout.next_row;
+        let sliding = open < 1.0;
+        // a closing body is only drawn, its widgets can't be used while they slide away
+        let no_interact = self.no_interact;
+        if sliding {
+            let height = self.anims.target(height_id).unwrap_or(0.0);
+            let body = layout.body;
+            self.push_clip_rect(rect(body.x, body.y + start, body.width, (height * open) as i32));
+            self.no_interact |= !expanded;
+        }
+        f(self);
+        if sliding {
+            self.no_interact = no_interact;
+            self.pop_clip_rect();
+        }
+
+        let measured = self.layout.top().next_row - start;
+        self.anims.set(height_id, measured as Real, time);
+        if sliding {
+            self.layout.end_rows_at(&layout, start + (measured as Real * open) as i32);
+        }
+    }
+
     fn clamp(x: i32, a: i32, b: i32) -> i32 {
         min(b, max(a, x))
     }
 
+    // wheel scrolling eases toward the accumulated target instead of jumping there
+    fn smooth_scroll(&mut self, anim_id: Id, current: i32, delta: i32, maxscroll: i32) -> i32 {
+        let time = self.input.borrow().time();
+        if delta == 0 && !self.anims.is_running(anim_id, time) {
+            // a finished scroll lands on its target, the last eased frame may have stopped short of it
+            let end = self.anims.target(anim_id).map_or(current, |t| t.round() as i32);
+            self.anims.remove(anim_id);
+            return end;
+        }
+        let target = self.anims.target(anim_id).unwrap_or(current as Real) + delta as Real;
+        let target = target.clamp(0.0, maxscroll as Real);
+        self.anims
+            .tween(anim_id, current as Real, target, SMOOTH_SCROLL_MS, Easing::EaseOut, time)
+            .round() as i32
+    }
+
+    // notes the axis as one the wheel can move while the mouse is over the body
+    fn offer_scroll(&mut self, body: Recti, vertical: bool, anim_id: Id, scroll: i32, maxscroll: i32) {
+        let in_hover_root = self.in_hover_root;
+        if !self.mouse_over(body, in_hover_root) {
+            return;
+        }
+        let scroll = self.anims.target(anim_id).map_or(scroll, |t| t.round() as i32);
+        self.scrollable[vertical as usize] = Some(Scrollable { anim_id, scroll, max: maxscroll });
+    }
+
+    // gives the wheel to the innermost hovered panel first, an axis a container can't move along any more goes on to
+    // the container around it. Returns the part of the delta no container took.
+    pub(crate) fn take_wheel(&mut self, delta: Vec2i) -> Vec2i {
+        let mut delta = delta;
+        for panel in &mut self.panels {
+            delta = panel.inner_mut().take_wheel(delta);
+        }
+        for (vertical, d) in [(false, &mut delta.x), (true, &mut delta.y)] {
+            let target = match self.scrollable[vertical as usize] {
+                Some(target) if *d != 0 && target.can_move(*d) => target,
+                _ => continue,
+            };
+            let current = if vertical { self.scroll.y } else { self.scroll.x };
+            self.smooth_scroll(target.anim_id, current, *d, target.max);
+            *d = 0;
+        }
+        delta
+    }
+
     #[inline(never)]
     fn scrollbars(&mut self, body: &mut Recti) {
         let sz = self.style.scrollbar_size;
@@ -503,9 +1153,10 @@ impl<PR: Clone> Container<PR> {
             let mut base = body;
             base.x = body.x + body.width;
             base.width = self.style.scrollbar_size;
-            self.update_control(id, base, WidgetOption::NONE);
+            self.update_control(id, base, WidgetOption::NO_NAV);
             if self.focus == Some(id) && self.input.borrow().mouse_down.is_left() {
                 self.scroll.y += self.input.borrow().mouse_delta.y * cs.y / base.height;
+                self.anims.remove(IdManager::get_sub_id(id, "!smooth"));
             }
 
             self.draw_frame(base, ControlColor::ScrollBase);
@@ -517,12 +1168,10 @@ impl<PR: Clone> Container<PR> {
             };
             thumb.y += self.scroll.y * (base.height - thumb.height) / maxscroll;
             self.draw_frame(thumb, ControlColor::ScrollThumb);
-            let in_hover_root = self.in_hover_root;
-            if self.mouse_over(body, in_hover_root) {
-                // TODO: doesn't solve the issue where we have a panel inside a panel
-                self.scroll.y += self.input.borrow().scroll_delta.y;
-            }
+            let anim_id = IdManager::get_sub_id(id, "!smooth");
+            self.scroll.y = self.smooth_scroll(anim_id, self.scroll.y, 0, maxscroll);
             self.scroll.y = Self::clamp(self.scroll.y, 0, maxscroll);
+            self.offer_scroll(body, true, anim_id, self.scroll.y, maxscroll);
         } else {
             self.scroll.y = 0;
         }
@@ -532,9 +1181,10 @@ impl<PR: Clone> Container<PR> {
             let mut base_0 = body;
             base_0.y = body.y + body.height;
             base_0.height = self.style.scrollbar_size;
-            self.update_control(id_0, base_0, WidgetOption::NONE);
+            self.update_control(id_0, base_0, WidgetOption::NO_NAV);
             if self.focus == Some(id_0) && self.input.borrow().mouse_down.is_left() {
                 self.scroll.x += self.input.borrow().mouse_delta.x * cs.x / base_0.width;
+                self.anims.remove(IdManager::get_sub_id(id_0, "!smooth"));
             }
 
             self.draw_frame(base_0, ControlColor::ScrollBase);
@@ -546,11 +1196,10 @@ impl<PR: Clone> Container<PR> {
             };
             thumb_0.x += self.scroll.x * (base_0.width - thumb_0.width) / maxscroll_0;
             self.draw_frame(thumb_0, ControlColor::ScrollThumb);
-            let in_hover_root = self.in_hover_root;
-            if self.mouse_over(body, in_hover_root) {
-                self.scroll.x += self.input.borrow().scroll_delta.x;
-            }
+            let anim_id = IdManager::get_sub_id(id_0, "!smooth");
+            self.scroll.x = self.smooth_scroll(anim_id, self.scroll.x, 0, maxscroll_0);
             self.scroll.x = Self::clamp(self.scroll.x, 0, maxscroll_0);
+            self.offer_scroll(body, false, anim_id, self.scroll.x, maxscroll_0);
         } else {
             self.scroll.x = 0;
         }
@@ -559,6 +1208,7 @@ impl<PR: Clone> Container<PR> {
 
     pub fn push_container_body(&mut self, body: Recti, opt: WidgetOption) {
         let mut body = body;
+        self.scrollable = [None; 2];
         if !opt.has_no_scroll() {
             self.scrollbars(&mut body);
         }
@@ -568,6 +1218,8 @@ impl<PR: Clone> Container<PR> {
         self.layout.push_layout(expand_rect(body, padding), scroll);
         self.layout.style = self.style.clone();
         self.body = body;
+        // the scrollbars are not widgets of the body
+        self.last_control = None;
     }
 
     fn pop_panel(&mut self, panel: &mut ContainerHandle<PR>) {
@@ -591,11 +1243,14 @@ impl<PR: Clone> Container<PR> {
         }
 
         container.in_hover_root = self.in_hover_root;
+        container.no_interact = self.no_interact;
+        container.nav = self.nav.clone();
         container.push_container_body(rect, opt);
         container.push_clip_rect(clip_rect);
     }
 
     fn end_panel(&mut self, panel: &mut ContainerHandle<PR>) {
+        panel.inner_mut().draw_nav_ring();
         panel.inner_mut().pop_clip_rect();
         self.pop_panel(panel);
         self.panels.push(panel.clone())
@@ -647,9 +1302,12 @@ impl<PR: Clone> Container<PR> {
         };
         let r: Recti = self.layout.next();
         self.update_control(id, r, opt);
-        if self.input.borrow().mouse_pressed.is_left() && self.focus == Some(id) {
+        if self.is_activated(id) {
             res |= ResourceState::SUBMIT;
         }
+        if self.is_double_clicked(id) {
+            res |= ResourceState::DOUBLE_CLICK;
+        }
         self.draw_control_frame(id, r, ControlColor::Button, opt);
         if label.len() > 0 {
             self.draw_control_text(label, r, ControlColor::Text, opt);
@@ -664,6 +1322,54 @@ impl<PR: Clone> Container<PR> {
         return res;
     }
 
+    // a button that is also pressed by the chord registered for `action`, shown as a hint on its right
+    #[inline(never)]
+    pub fn button_shortcut(&mut self, label: &str, action: &str, opt: WidgetOption) -> ResourceState {
+        let mut res = ResourceState::NONE;
+        let id: Id = self.idmngr.get_id_from_str(label);
+        let r: Recti = self.layout.next();
+        self.update_control(id, r, opt);
+        if self.is_activated(id) || self.is_shortcut_fired(action) {
+            res |= ResourceState::SUBMIT;
+        }
+        if self.is_double_clicked(id) {
+            res |= ResourceState::DOUBLE_CLICK;
+        }
+        self.draw_control_frame(id, r, ControlColor::Button, opt);
+        let mut label_rect = r;
+        if let Some(shortcut) = self.shortcut(action) {
+            label_rect.width -= self.draw_shortcut_hint(&shortcut, r);
+        }
+        self.draw_control_text(label, label_rect, ControlColor::Text, opt);
+        res
+    }
+
+    pub(crate) fn dimmed_text_color(&self) -> Color {
+        mix_color(
+            self.style.colors[ControlColor::Text as usize],
+            self.style.colors[ControlColor::Button as usize],
+            0.4,
+        )
+    }
+
+    // draws the chord right aligned and dimmed inside the rectangle, returns the width it takes
+    pub(crate) fn draw_shortcut_hint(&mut self, shortcut: &Shortcut, r: Recti) -> i32 {
+        let hint = shortcut.to_string();
+        let font = self.style.font;
+        let padding = self.style.padding;
+        let tsize = self.atlas.get_text_size(font, hint.as_str());
+        let color = self.dimmed_text_color();
+        self.push_clip_rect(r);
+        self.draw_text(
+            font,
+            hint.as_str(),
+            vec2(r.x + r.width - padding - tsize.width, r.y + (r.height - tsize.height) / 2),
+            color,
+        );
+        self.pop_clip_rect();
+        tsize.width + padding
+    }
+
     #[inline(never)]
     pub fn button_ex2(&mut self, label: &str, slot: Option<SlotId>, opt: WidgetOption) -> ResourceState {
         let mut res = ResourceState::NONE;
@@ -674,9 +1380,12 @@ impl<PR: Clone> Container<PR> {
         };
         let r: Recti = self.layout.next();
         self.update_control(id, r, opt);
-        if self.input.borrow().mouse_pressed.is_left() && self.focus == Some(id) {
+        if self.is_activated(id) {
             res |= ResourceState::SUBMIT;
         }
+        if self.is_double_clicked(id) {
+            res |= ResourceState::DOUBLE_CLICK;
+        }
         self.draw_control_frame(id, r, ControlColor::Button, opt);
         if label.len() > 0 {
             self.draw_control_text(label, r, ControlColor::Text, opt);
@@ -701,9 +1410,12 @@ impl<PR: Clone> Container<PR> {
         };
         let r: Recti = self.layout.next();
         self.update_control(id, r, opt);
-        if self.input.borrow().mouse_pressed.is_left() && self.focus == Some(id) {
+        if self.is_activated(id) {
             res |= ResourceState::SUBMIT;
         }
+        if self.is_double_clicked(id) {
+            res |= ResourceState::DOUBLE_CLICK;
+        }
         self.draw_control_frame(id, r, ControlColor::Button, opt);
         if label.len() > 0 {
             self.draw_control_text(label, r, ControlColor::Text, opt);
@@ -725,7 +1437,7 @@ impl<PR: Clone> Container<PR> {
         let mut r: Recti = self.layout.next();
         let box_0: Recti = rect(r.x, r.y, r.height, r.height);
         self.update_control(id, r, WidgetOption::NONE);
-        if self.input.borrow().mouse_pressed.is_left() && self.focus == Some(id) {
+        if self.is_activated(id) {
             res |= ResourceState::CHANGE;
             *state = *state == false;
         }
@@ -739,54 +1451,622 @@ impl<PR: Clone> Container<PR> {
         return res;
     }
 
+    // a checkbox cycling through checked and unchecked, a mixed state set by the caller becomes checked when clicked
+    #[inline(never)]
+    pub fn checkbox_tristate(&mut self, label: &str, state: &mut CheckState) -> ResourceState {
+        let mut res = ResourceState::NONE;
+        let id: Id = self.idmngr.get_id_from_str(label);
+        let r: Recti = self.layout.next();
+        let box_0: Recti = rect(r.x, r.y, r.height, r.height);
+        self.update_control(id, r, WidgetOption::NONE);
+        if self.is_activated(id) {
+            res |= ResourceState::CHANGE;
+            *state = if state.is_checked() { CheckState::Unchecked } else { CheckState::Checked };
+        }
+        self.draw_control_frame(id, box_0, ControlColor::Base, WidgetOption::NONE);
+        let color = self.style.colors[ControlColor::Text as usize];
+        match state {
+            CheckState::Checked => self.draw_icon(CHECK_ICON, box_0, color),
+            CheckState::Mixed => {
+                let inset = box_0.height / 4;
+                self.draw_rect(rect(box_0.x + inset, box_0.y + box_0.height / 2 - 1, box_0.width - inset * 2, 2), color);
+            }
+            CheckState::Unchecked => (),
+        }
+        let text = rect(r.x + box_0.width, r.y, r.width - box_0.width, r.height);
+        self.draw_control_text(label, text, ControlColor::Text, WidgetOption::NONE);
+        res
+    }
+
+    // one choice of a group bound to `value`, picking it stores `option`. The button is keyed by its label, push an id
+    // around groups that reuse labels.
+    #[inline(never)]
+    pub fn radio<T: PartialEq + Clone>(&mut self, label: &str, value: &mut T, option: T) -> ResourceState {
+        let mut res = ResourceState::NONE;
+        let id: Id = self.idmngr.get_id_from_str(label);
+        let r: Recti = self.layout.next();
+        let box_0: Recti = rect(r.x, r.y, r.height, r.height);
+        self.update_control(id, r, WidgetOption::NONE);
+        if self.is_activated(id) && *value != option {
+            res |= ResourceState::CHANGE;
+            *value = option.clone();
+        }
+        self.draw_control_frame(id, box_0, ControlColor::Base, WidgetOption::NONE);
+        if *value == option {
+            let color = self.style.colors[ControlColor::Text as usize];
+            self.draw_rect(expand_rect(box_0, -box_0.height / 3), color);
+        }
+        let text = rect(r.x + box_0.width, r.y, r.width - box_0.width, r.height);
+        self.draw_control_text(label, text, ControlColor::Text, WidgetOption::NONE);
+        res
+    }
+
+    // a switch whose knob slides to the right when on
+    #[inline(never)]
+    pub fn toggle(&mut self, label: &str, state: &mut bool) -> ResourceState {
+        let mut res = ResourceState::NONE;
+        let id: Id = self.idmngr.get_id_from_str(label);
+        let r: Recti = self.layout.next();
+        let track = rect(r.x, r.y, r.height * 2, r.height);
+        self.update_control(id, r, WidgetOption::NONE);
+        if self.is_activated(id) {
+            res |= ResourceState::CHANGE;
+            *state = !*state;
+        }
+        let time = self.input.borrow().time();
+        let target = if *state { 1.0 } else { 0.0 };
+        let on = self
+            .anims
+            .tween(IdManager::get_sub_id(id, "!knob"), target, target, TOGGLE_SLIDE_MS, Easing::EaseInOut, time);
+        let track_color = mix_color(
+            self.style.colors[ControlColor::Base as usize],
+            self.style.colors[ControlColor::ButtonFocus as usize],
+            on,
+        );
+        self.draw_frame_color(track, track_color, ControlColor::Base);
+        let knob = rect(
+            track.x + ((track.width - track.height) as Real * on) as i32,
+            track.y,
+            track.height,
+            track.height,
+        );
+        self.draw_control_frame(id, expand_rect(knob, -2), ControlColor::Button, WidgetOption::NONE);
+        let text = rect(r.x + track.width, r.y, r.width - track.width, r.height);
+        self.draw_control_text(label, text, ControlColor::Text, WidgetOption::NONE);
+        res
+    }
+
+    // a bar filled up to `fraction` of its width with the percentage over it
+    pub fn progress_bar(&mut self, fraction: Real) {
+        let text = format!("{}%", (fraction.clamp(0.0, 1.0) * 100.0).round() as i32);
+        self.progress_bar_ex(fraction, Some(text.as_str()));
+    }
+
+    // a bar filled up to `fraction` of its width, with the text centered over it when given
+    pub fn progress_bar_ex(&mut self, fraction: Real, text: Option<&str>) {
+        let r = self.layout.next();
+        self.draw_frame(r, ControlColor::Base);
+        let filled = (r.width as Real * fraction.clamp(0.0, 1.0)).round() as i32;
+        if filled > 0 {
+            let color = self.style.colors[ControlColor::ButtonFocus as usize];
+            self.draw_rect(rect(r.x, r.y, filled, r.height), color);
+        }
+        if let Some(text) = text {
+            self.draw_control_text(text, r, ControlColor::Text, WidgetOption::ALIGN_CENTER);
+        }
+    }
+
+    // a bar with a segment sweeping back and forth for work of unknown length, it moves with the frame clock
+    pub fn busy_bar(&mut self) {
+        let r = self.layout.next();
+        self.draw_frame(r, ControlColor::Base);
+        let time = self.input.borrow().time();
+        let t = (time % BUSY_SWEEP_MS) as Real / BUSY_SWEEP_MS as Real;
+        // there and back again over one sweep
+        let t = Easing::EaseInOut.apply(1.0 - (t * 2.0 - 1.0).abs());
+        let width = r.width / 3;
+        let x = r.x + ((r.width - width) as Real * t).round() as i32;
+        let color = self.style.colors[ControlColor::ButtonFocus as usize];
+        self.draw_rect(rect(x, r.y, width, r.height), color);
+    }
+
+    // a ring of dots turning with the frame clock, the leading dot is the brightest
+    pub fn spinner(&mut self) {
+        const DOTS: usize = 8;
+        let r = self.layout.next();
+        let size = min(r.width, r.height);
+        let center = vec2(r.x + r.width / 2, r.y + r.height / 2);
+        let dot = max(size / 6, 2);
+        let radius = (size - dot) as Real / 2.0;
+        let time = self.input.borrow().time();
+        let lead = (time % SPINNER_TURN_MS) as usize * DOTS / SPINNER_TURN_MS as usize;
+        let base = self.style.colors[ControlColor::Base as usize];
+        let text = self.style.colors[ControlColor::Text as usize];
+        for i in 0..DOTS {
+            let angle = i as Real * std::f32::consts::TAU / DOTS as Real;
+            let x = center.x + (radius * angle.cos()).round() as i32 - dot / 2;
+            let y = center.y + (radius * angle.sin()).round() as i32 - dot / 2;
+            let age = (lead + DOTS - i) % DOTS;
+            let color = mix_color(text, base, age as Real / DOTS as Real);
+            self.draw_rect(rect(x, y, dot, dot), color);
+        }
+    }
+
+    // a button showing the selected item that opens the list of items below it, `label` identifies the combo and shows
+    // while no item is selected. Up and down move the selection while the list is open, return closes it.
+    #[inline(never)]
+    pub fn combo(&mut self, label: &str, selected: &mut usize, items: &[&str]) -> ResourceState {
+        let mut res = ResourceState::NONE;
+        let id: Id = self.idmngr.get_id_from_str(label);
+        let owner = (self as *const Self as usize, Some(IdManager::get_sub_id(id, "!combo")));
+        let open = self.shared.menu.borrow().is_open_for(owner);
+        let r: Recti = self.layout.next();
+        let opt = if open { WidgetOption::HOLD_FOCUS } else { WidgetOption::NONE };
+        self.update_control(id, r, opt);
+        if self.is_activated(id) {
+            if open {
+                self.shared.menu.borrow_mut().close();
+            } else {
+                self.shared.menu.borrow_mut().request(owner, r);
+            }
+        }
+
+        let mut moved = false;
+        if open && self.focus == Some(id) && !items.is_empty() {
+            let input = self.input.borrow();
+            let current = min(*selected, items.len() - 1);
+            if input.is_key_pressed(Key::Down) {
+                *selected = if *selected < items.len() { min(current + 1, items.len() - 1) } else { 0 };
+                moved = true;
+            } else if input.is_key_pressed(Key::Up) {
+                *selected = current.saturating_sub(1);
+                moved = true;
+            }
+            if input.is_key_pressed(Key::Return) {
+                self.shared.menu.borrow_mut().close();
+            }
+            drop(input);
+            if moved {
+                res |= ResourceState::CHANGE;
+            }
+        }
+
+        self.draw_control_frame(id, r, ControlColor::Button, WidgetOption::NONE);
+        let icon = rect(r.x + r.width - r.height, r.y, r.height, r.height);
+        let text = items.get(*selected).copied().unwrap_or(label);
+        self.draw_control_text(text, rect(r.x, r.y, r.width - icon.width, r.height), ControlColor::Text, WidgetOption::NONE);
+        let color = self.style.colors[ControlColor::Text as usize];
+        self.draw_icon(EXPAND_ICON, icon, color);
+
+        if !self.shared.menu.borrow().is_open_for(owner) {
+            return res;
+        }
+        let row_height = self.style.default_cell_size.height + self.style.padding * 2;
+        let rows = min(items.len(), DROPDOWN_ROWS) as i32;
+        let height = rows * (row_height + self.style.spacing) - self.style.spacing + self.style.padding * 2;
+        let (mut window, screen) = self.menu_window(0);
+        let reveal = moved || self.shared.menu.borrow().is_first_draw(0);
+        let current = *selected;
+        let mut picked = None;
+        window.dropdown(r, height, screen, |c| {
+            c.layout.row(&[-1], row_height);
+            for (i, item) in items.iter().enumerate() {
+                let item_id = c.idmngr.get_id_u32(i as u32);
+                let r = c.layout.next();
+                c.update_control(item_id, r, WidgetOption::NO_NAV);
+                if c.hover == Some(item_id) {
+                    c.draw_frame(r, ControlColor::ButtonHover);
+                } else if i == current {
+                    c.draw_frame(r, ControlColor::Button);
+                }
+                c.draw_control_text(item, r, ControlColor::Text, WidgetOption::NONE);
+                if c.is_activated(item_id) {
+                    picked = Some(i);
+                }
+                // keeps the selected item in view, the scroll applies from the next draw
+                if i == current && reveal {
+                    let body = c.body;
+                    let padding = c.style.padding;
+                    if r.y < body.y + padding {
+                        c.scroll.y -= body.y + padding - r.y;
+                    } else if r.y + r.height > body.y + body.height - padding {
+                        c.scroll.y += r.y + r.height - (body.y + body.height - padding);
+                    }
+                }
+            }
+        });
+        if let Some(i) = picked {
+            if *selected != i {
+                res |= ResourceState::CHANGE;
+            }
+            *selected = i;
+            self.shared.menu.borrow_mut().close();
+        }
+        res
+    }
+
+    // lays out `count` rows of the given height and hands only the visible ones to `f` with their index and
+    // rectangle, the others just take their space so scrolling over them works as if they were there
+    pub fn clipped_rows<F: FnMut(&mut Self, usize, Recti)>(&mut self, count: usize, height: i32, f: F) {
+        self.clipped_rows_of_width(count, -1, height, f)
+    }
+
+    fn clipped_rows_of_width<F: FnMut(&mut Self, usize, Recti)>(&mut self, count: usize, width: i32, height: i32, mut f: F) {
+        let spacing = self.style.spacing;
+        let step = height + spacing;
+        let clip = self.get_clip_rect();
+        let origin = self.layout.top().body.y + self.layout.top().next_row;
+        let first = ((clip.y - origin) / step).clamp(0, count as i32);
+        let last = ((clip.y + clip.height - origin + step - 1) / step).clamp(first, count as i32);
+
+        // the rows out of view are laid out as one empty row each side of the visible ones
+        let skip = |c: &mut Self, rows: i32| {
+            if rows > 0 {
+                c.layout.row(&[width], rows * step - spacing);
+                c.layout.next();
+            }
+        };
+        skip(self, first);
+        for i in first..last {
+            self.layout.row(&[width], height);
+            let r = self.layout.next();
+            f(self, i as usize, r);
+        }
+        skip(self, count as i32 - last);
+    }
+
+    // the keyboard side of a list once it has the focus, from a click on a row or from the keyboard navigation: the
+    // arrows, page keys, home and end move the cursor, ctrl+space toggles the cursor row, ctrl+a selects all and return
+    // submits. A press outside the body gives the focus back.
+    fn list_keys(&mut self, list_id: Id, selection: &mut ListSelection, count: usize, row_height: i32) -> ResourceState {
+        let mut res = ResourceState::NONE;
+        let step = row_height + self.style.spacing;
+        let body = self.body;
+        let mods = self.input.borrow().key_mode();
+        self.update_control(list_id, body, WidgetOption::HOLD_FOCUS);
+        if self.focus != Some(list_id) {
+            return res;
+        }
+
+        // the arrows belong to the list rather than the window's keyboard navigation
+        self.nav.borrow_mut().editing = true;
+        let page = max(body.height / step, 1) as usize;
+        let last = count.saturating_sub(1);
+        let target = {
+            let input = self.input.borrow();
+            let cursor = selection.cursor();
+            let from = cursor.unwrap_or(0);
+            if count == 0 {
+                None
+            } else if input.is_key_pressed(Key::Down) {
+                Some(cursor.map_or(0, |c| min(c + 1, last)))
+            } else if input.is_key_pressed(Key::Up) {
+                Some(from.saturating_sub(1))
+            } else if input.is_key_pressed(Key::PageDown) {
+                Some(min(from + page, last))
+            } else if input.is_key_pressed(Key::PageUp) {
+                Some(from.saturating_sub(page))
+            } else if input.is_key_pressed(Key::Home) {
+                Some(0)
+            } else if input.is_key_pressed(Key::End) {
+                Some(last)
+            } else {
+                None
+            }
+        };
+        let (space, select_all, submit) = {
+            let input = self.input.borrow();
+            (
+                input.is_key_pressed(Key::Space),
+                mods.is_ctrl() && input.is_key_pressed(Key::A),
+                input.is_key_pressed(Key::Return),
+            )
+        };
+        if let Some(target) = target {
+            if selection.move_cursor(target, mods) {
+                res |= ResourceState::CHANGE;
+            }
+            // keeps the cursor in view, the scroll applies from the next frame
+            let top = self.layout.top().body.y + self.layout.top().next_row + target as i32 * step;
+            let padding = self.style.padding;
+            if top < body.y + padding {
+                self.scroll.y -= body.y + padding - top;
+            } else if top + row_height > body.y + body.height - padding {
+                self.scroll.y += top + row_height - (body.y + body.height - padding);
+            }
+        }
+        if space && selection.toggle_cursor() {
+            res |= ResourceState::CHANGE;
+        }
+        if select_all && selection.select_all(count) {
+            res |= ResourceState::CHANGE;
+        }
+        if submit && selection.cursor().is_some() {
+            res |= ResourceState::SUBMIT;
+        }
+        res
+    }
+
+    // picks the row with the mouse and draws its selection, hover and cursor behind the row content
+    fn list_row(&mut self, list_id: Id, selection: &mut ListSelection, index: usize, r: Recti) -> ResourceState {
+        let mut res = ResourceState::NONE;
+        let row_id = self.idmngr.get_id_u32(index as u32);
+        let focused = self.focus == Some(list_id);
+        self.update_control(row_id, r, WidgetOption::NO_NAV);
+        if self.is_activated(row_id) {
+            let mods = self.input.borrow().key_mode();
+            if selection.click(index, mods) {
+                res |= ResourceState::CHANGE;
+            }
+            if self.is_double_clicked(row_id) {
+                res |= ResourceState::SUBMIT;
+            }
+            self.set_focus(Some(list_id));
+        }
+        if selection.is_selected(index) {
+            self.draw_frame(r, ControlColor::ButtonFocus);
+        } else if self.hover == Some(row_id) {
+            self.draw_frame(r, ControlColor::ButtonHover);
+        }
+        if focused && selection.cursor() == Some(index) {
+            let color = self.style.colors[ControlColor::FocusRing as usize];
+            self.draw_box(r, color);
+        }
+        res
+    }
+
+    // a scrolling list of `count` rows in the panel, `item` gives the text of a row and is only called for the rows
+    // in view. Rows are picked with the mouse, ctrl and shift extend a multi selection, the arrows, page keys, home and
+    // end move the cursor once the list has the focus. Double clicking or return on the cursor submits.
     #[inline(never)]
+    pub fn list_box<S: AsRef<str>, F: FnMut(usize) -> S>(
+        &mut self,
+        panel: &mut ContainerHandle<PR>,
+        selection: &mut ListSelection,
+        count: usize,
+        mut item: F,
+    ) -> ResourceState {
+        let mut res = ResourceState::NONE;
+        selection.truncate(count);
+        self.panel(panel, WidgetOption::NONE, |handle| {
+            let c = &mut handle.inner_mut();
+            let list_id = c.idmngr.get_id_from_str("!list");
+            let row_height = c.style.default_cell_size.height + c.style.padding * 2;
+            res |= c.list_keys(list_id, selection, count, row_height);
+            c.clipped_rows(count, row_height, |c, i, r| {
+                res |= c.list_row(list_id, selection, i, r);
+                c.draw_control_text(item(i).as_ref(), r, ControlColor::Text, WidgetOption::NONE);
+            });
+        });
+        res
+    }
+
+    fn draw_sort_arrow(&mut self, r: Recti, order: SortOrder) {
+        let color = self.style.colors[ControlColor::Text as usize];
+        let size = max(r.height / 4, 2);
+        let x = r.x + r.width / 2;
+        let y = r.y + (r.height - size) / 2;
+        for i in 0..size {
+            let row = if order.is_ascending() { i } else { size - 1 - i };
+            self.draw_rect(rect(x - row, y + i, row * 2 + 1, 1), color);
+        }
+    }
+
+    // the header of a table over its rows: a click sorts the sortable columns, the gaps between the columns resize
+    // them and dragging a column moves it. `left` is where the rows start so the header follows them when they scroll,
+    // `name` scopes the ids of its controls.
+    fn table_header(&mut self, state: &mut TableState, name: &str, header: Recti, left: i32) {
+        let spacing = self.style.spacing;
+        let in_hover_root = self.in_hover_root;
+        let widths = state.widths();
+        let order = state.order().to_vec();
+        self.idmngr.push_id_from_str(name);
+        self.push_clip_rect(header);
+        let mut x = left;
+        for (&column, &width) in order.iter().zip(widths.iter()) {
+            let r = rect(x, header.y, width, header.height);
+            let id = self.idmngr.get_id_u32(column as u32);
+            let was_focused = self.focus == Some(id);
+            self.update_control(id, r, WidgetOption::NO_NAV);
+            self.drag_source(state.columns[column].label.as_str(), || ColumnMove(column));
+            let clicked =
+                was_focused && !self.input.borrow().mouse_down.is_left() && !self.shared.drag.borrow().is_active() && self.mouse_over(r, in_hover_root);
+            if clicked && state.columns[column].sortable {
+                state.toggle_sort(column);
+            }
+            if let Some(ColumnMove(from)) = self.drop_target::<ColumnMove>() {
+                state.move_column(from, column);
+            }
+
+            self.draw_control_frame(id, r, ControlColor::Button, WidgetOption::NONE);
+            let arrow = rect(r.x + r.width - r.height, r.y, r.height, r.height);
+            let sort = state.sort().filter(|&(c, _)| c == column);
+            let text = if sort.is_some() { rect(r.x, r.y, r.width - arrow.width, r.height) } else { r };
+            self.draw_control_text(state.columns[column].label.as_str(), text, ControlColor::Text, WidgetOption::NONE);
+            if let Some((_, order)) = sort {
+                self.draw_sort_arrow(arrow, order);
+            }
+
+            let handle = rect(x + width - 2, header.y, spacing + 4, header.height);
+            let handle_id = IdManager::get_sub_id(id, "!resize");
+            self.update_control(handle_id, handle, WidgetOption::NO_NAV);
+            self.set_control_cursor(handle_id, CursorIcon::ResizeEW);
+            if self.focus == Some(handle_id) && self.input.borrow().mouse_down.is_left() {
+                let delta = self.input.borrow().mouse_delta.x;
+                state.columns[column].width = max(width + delta, MIN_COLUMN_WIDTH);
+            }
+            x += width + spacing;
+        }
+        self.pop_clip_rect();
+        self.idmngr.pop_id();
+    }
+
+    // a table of `rows` rows in the panel under a header that stays in place when the rows scroll vertically. `cell`
+    // fills the cell of a row and column, it is only called for the rows in view with the layout set to the cell so a
+    // label or any other widget fills it, the widget ids are scoped by row and column. Rows are selected like in
+    // `list_box`, the header sorts, resizes and moves the columns of the state, the rows are expected to follow
+    // `state.sort()`.
+    #[inline(never)]
+    pub fn table<F: FnMut(&mut Self, usize, usize)>(
+        &mut self,
+        panel: &mut ContainerHandle<PR>,
+        state: &mut TableState,
+        rows: usize,
+        mut cell: F,
+    ) -> ResourceState {
+        let mut res = ResourceState::NONE;
+        state.begin_frame();
+        state.selection.truncate(rows);
+        let row_height = self.style.default_cell_size.height + self.style.padding * 2;
+        let spacing = self.style.spacing;
+        let widths = state.widths();
+        let order = state.order().to_vec();
+        let total = widths.iter().sum::<i32>() + spacing * (widths.len() as i32 - 1);
+        // the header is scoped by the panel like the rows
+        let name = panel.inner().name.clone();
+        self.column(|c| {
+            let saved = *c.layout.top();
+            c.layout.row(&[-1], row_height);
+            let header = c.layout.next();
+            // the rows start right below the header
+            c.layout.end_rows_at(&saved, saved.next_row + row_height);
+            c.layout.row(&[-1], -1);
+            let mut left = header.x;
+            c.panel(panel, WidgetOption::NONE, |handle| {
+                let c = &mut handle.inner_mut();
+                let table_id = c.idmngr.get_id_from_str("!table");
+                res |= c.list_keys(table_id, &mut state.selection, rows, row_height);
+                let layout = *c.layout.top();
+                left = layout.body.x;
+                // the rows are as wide as the columns, or as the body when they don't fill it
+                let width = max(total, layout.body.width - layout.indent);
+                let mut stripe = c.style.colors[ControlColor::Base as usize];
+                stripe.a /= 2;
+                c.clipped_rows_of_width(rows, width, row_height, |c, i, r| {
+                    if i % 2 == 1 {
+                        c.draw_rect(r, stripe);
+                    }
+                    res |= c.list_row(table_id, &mut state.selection, i, r);
+                    let row_id = c.idmngr.get_id_u32(i as u32);
+                    c.idmngr.push_id(row_id);
+                    let mut x = 0;
+                    for (k, &column) in order.iter().enumerate() {
+                        c.layout.cell_at(x, widths[k]);
+                        let column_id = c.idmngr.get_id_u32(column as u32);
+                        c.idmngr.push_id(column_id);
+                        cell(c, i, column);
+                        c.idmngr.pop_id();
+                        x += widths[k] + spacing;
+                    }
+                    c.idmngr.pop_id();
+                });
+            });
+            c.table_header(state, name.as_str(), header, left);
+        });
+        res
+    }
+
     pub fn textbox_raw(&mut self, buf: &mut String, id: Id, r: Recti, opt: WidgetOption) -> ResourceState {
+        self.textbox_field(buf, id, r, &TextField::default(), opt)
+    }
+
+    #[inline(never)]
+    pub fn textbox_field(&mut self, buf: &mut String, id: Id, r: Recti, field: &TextField, opt: WidgetOption) -> ResourceState {
         let mut res = ResourceState::NONE;
         self.update_control(id, r, opt | WidgetOption::HOLD_FOCUS);
+        self.set_control_cursor(id, CursorIcon::IBeam);
+        if self.is_double_clicked(id) {
+            res |= ResourceState::DOUBLE_CLICK;
+        }
         if self.focus == Some(id) {
-            let mut len = buf.len();
+            let mut len = buf.chars().count();
 
             if self.input.borrow().input_text.len() > 0 {
-                buf.push_str(self.input.borrow().input_text.as_str());
-                len += self.input.borrow().input_text.len() as usize;
-                res |= ResourceState::CHANGE
+                for c in self.input.borrow().input_text.chars() {
+                    if field.max_length.is_some_and(|max_len| len >= max_len) {
+                        break;
+                    }
+                    if field.filter.accepts(c) {
+                        buf.push(c);
+                        len += 1;
+                        res |= ResourceState::CHANGE
+                    }
+                }
             }
 
-            if self.input.borrow().key_pressed.is_backspace() && len > 0 {
+            // while composing, backspace and return belong to the input method
+            let composing = self.input.borrow().is_composing();
+            if self.input.borrow().key_pressed.is_backspace() && len > 0 && !composing {
                 // skip utf-8 continuation bytes
                 buf.pop();
                 res |= ResourceState::CHANGE
             }
-            if self.input.borrow().key_pressed.is_return() {
+            if self.input.borrow().key_pressed.is_return() && !composing && field.is_valid(buf.as_str()) {
                 self.set_focus(None);
                 res |= ResourceState::SUBMIT;
             }
         }
         self.draw_control_frame(id, r, ControlColor::Base, opt);
+        if !field.is_valid(buf.as_str()) {
+            let color = self.style.colors[ControlColor::Error as usize];
+            self.draw_box(expand_rect(r, 1), color);
+        }
+        let text = if opt.is_password() { "*".repeat(buf.chars().count()) } else { buf.clone() };
         if self.focus == Some(id) {
             let color = self.style.colors[ControlColor::Text as usize];
             let font = self.style.font;
-            let tsize = self.atlas.get_text_size(font, buf.as_str());
-            let ofx = r.width - self.style.padding - tsize.width - 1;
+            let composition = self.input.borrow().composition_text().to_string();
+            let composition = if opt.is_password() {
+                "*".repeat(composition.chars().count())
+            } else {
+                composition
+            };
+            let (cstart, clen) = self.input.borrow().composition_cursor();
+            let tsize = self.atlas.get_text_size(font, text.as_str());
+            let csize = self.atlas.get_text_size(font, composition.as_str());
+            let height = max(tsize.height, csize.height);
+            let ofx = r.width - self.style.padding - tsize.width - csize.width - 1;
             let textx = r.x + (if ofx < self.style.padding { ofx } else { self.style.padding });
-            let texty = r.y + (r.height - tsize.height) / 2;
+            let texty = r.y + (r.height - height) / 2;
 
             self.push_clip_rect(r);
-            self.draw_text(font, buf.as_str(), vec2(textx, texty), color);
-            self.draw_rect(rect(textx + tsize.width, texty, 1, tsize.height), color);
+            self.draw_text(font, text.as_str(), vec2(textx, texty), color);
+            let caret = if composition.is_empty() {
+                rect(textx + tsize.width, texty, 1, height)
+            } else {
+                // underline the whole preedit string and emphasize the segment being converted
+                let cx = textx + tsize.width;
+                let prefix: String = composition.chars().take(cstart).collect();
+                let segment: String = composition.chars().skip(cstart).take(clen).collect();
+                let sx = cx + self.atlas.get_text_size(font, prefix.as_str()).width;
+                let sw = self.atlas.get_text_size(font, segment.as_str()).width;
+                self.draw_text(font, composition.as_str(), vec2(cx, texty), color);
+                self.draw_rect(rect(cx, texty + height, csize.width, 1), color);
+                if sw > 0 {
+                    self.draw_rect(rect(sx, texty + height - 1, sw, 2), color);
+                }
+                rect(sx, texty, 1, height)
+            };
+            // the caret stays solid while typing and blinks when idle
+            let typing = !res.is_none() || !self.input.borrow().key_pressed.is_none() || self.input.borrow().is_composing();
+            let elapsed = self.anims.elapsed(IdManager::get_sub_id(id, "!caret"), typing, self.input.borrow().time());
+            if (elapsed / CARET_BLINK_MS) % 2 == 0 {
+                self.draw_rect(caret, color);
+            }
             self.pop_clip_rect();
+            self.caret_rect = Some(caret);
         } else {
-            self.draw_control_text(buf.as_str(), r, ControlColor::Text, opt);
+            self.draw_control_text(text.as_str(), r, ControlColor::Text, opt);
         }
         return res;
     }
 
     #[inline(never)]
-    fn number_textbox(&mut self, precision: usize, value: &mut Real, r: Recti, id: Id) -> ResourceState {
+    fn number_textbox<T: Numeric>(&mut self, precision: usize, value: &mut T, r: Recti, id: Id) -> ResourceState {
         if self.input.borrow().mouse_pressed.is_left() && self.input.borrow().key_down.is_shift() && self.hover == Some(id) {
             self.number_edit = Some(id);
             self.number_edit_buf.clear();
-            self.number_edit_buf.push_str(format!("{:.*}", precision, value).as_str());
+            self.number_edit_buf.push_str(value.format(precision).as_str());
         }
 
         if self.number_edit == Some(id) {
@@ -794,12 +2074,8 @@ impl<PR: Clone> Container<PR> {
             let res: ResourceState = self.textbox_raw(&mut temp, id, r, WidgetOption::NONE);
             self.number_edit_buf = temp;
             if res.is_submitted() || self.focus != Some(id) {
-                match self.number_edit_buf.parse::<f32>() {
-                    Ok(v) => {
-                        *value = v as Real;
-                        self.number_edit = None;
-                    }
-                    _ => (),
+                if let Some(v) = T::parse(self.number_edit_buf.as_str()) {
+                    *value = v;
                 }
                 self.number_edit = None;
             } else {
@@ -815,8 +2091,15 @@ impl<PR: Clone> Container<PR> {
         return self.textbox_raw(buf, id, r, opt);
     }
 
+    pub fn textbox_field_ex(&mut self, buf: &mut String, field: &TextField, opt: WidgetOption) -> ResourceState {
+        let id: Id = self.idmngr.get_id_from_ptr(buf);
+        let r: Recti = self.layout.next();
+        self.textbox_field(buf, id, r, field, opt)
+    }
+
+    // a slider from `low` to `high`, a `step` other than zero snaps the value to `low` plus a multiple of it
     #[inline(never)]
-    pub fn slider_ex(&mut self, value: &mut Real, low: Real, high: Real, step: Real, precision: usize, opt: WidgetOption) -> ResourceState {
+    pub fn slider_ex<T: Numeric>(&mut self, value: &mut T, low: T, high: T, step: T, precision: usize, opt: WidgetOption) -> ResourceState {
         let mut res = ResourceState::NONE;
         let last = *value;
         let mut v = last;
@@ -826,11 +2109,15 @@ impl<PR: Clone> Container<PR> {
             return res;
         }
         self.update_control(id, base, opt);
+        self.set_control_cursor(id, CursorIcon::ResizeEW);
+        let (lowf, highf) = (low.to_f64(), high.to_f64());
         if self.focus == Some(id) && (!self.input.borrow().mouse_down.is_none() | self.input.borrow().mouse_pressed.is_left()) {
-            v = low + (self.input.borrow().mouse_pos.x - base.x) as Real * (high - low) / base.width as Real;
+            let mut f = lowf + (self.input.borrow().mouse_pos.x - base.x) as f64 * (highf - lowf) / base.width as f64;
+            let step = step.to_f64();
             if step != 0. {
-                v = (v + step / 2 as Real) / step * step;
+                f = lowf + ((f - lowf) / step).round() * step;
             }
+            v = T::from_f64(f);
         }
         v = if high < (if low > v { low } else { v }) {
             high
@@ -845,35 +2132,181 @@ impl<PR: Clone> Container<PR> {
         }
         self.draw_control_frame(id, base, ControlColor::Base, opt);
         let w = self.style.thumb_size;
-        let x = ((v - low) * (base.width - w) as Real / (high - low)) as i32;
+        let x = ((v.to_f64() - lowf) * (base.width - w) as f64 / (highf - lowf)) as i32;
         let thumb = rect(base.x + x, base.y, w, base.height);
         self.draw_control_frame(id, thumb, ControlColor::Button, opt);
-        let mut buff = String::new();
-        buff.push_str(format!("{:.*}", precision, value).as_str());
-        self.draw_control_text(buff.as_str(), base, ControlColor::Text, opt);
+        self.draw_control_text(value.format(precision).as_str(), base, ControlColor::Text, opt);
         return res;
     }
 
-    #[inline(never)]
-    pub fn number_ex(&mut self, value: &mut Real, step: Real, precision: usize, opt: WidgetOption) -> ResourceState {
-        let mut res = ResourceState::NONE;
+    pub fn number_ex<T: Numeric>(&mut self, value: &mut T, step: Real, precision: usize, opt: WidgetOption) -> ResourceState {
         let id: Id = self.idmngr.get_id_from_ptr(value);
         let base: Recti = self.layout.next();
-        let last: Real = *value;
+        self.number_raw(value, id, base, step, precision, opt)
+    }
+
+    // a number field dragged horizontally by `step` per pixel, even a fraction of the unit of an integer, shift clicking it types the value in
+    #[inline(never)]
+    pub fn number_raw<T: Numeric>(&mut self, value: &mut T, id: Id, base: Recti, step: Real, precision: usize, opt: WidgetOption) -> ResourceState {
+        let mut res = ResourceState::NONE;
+        let last = *value;
         if !self.number_textbox(precision, value, base, id).is_none() {
             return res;
         }
         self.update_control(id, base, opt);
+        self.set_control_cursor(id, CursorIcon::ResizeEW);
         if self.focus == Some(id) && self.input.borrow().mouse_down.is_left() {
-            *value += self.input.borrow().mouse_delta.x as Real * step;
+            let input = self.input.borrow();
+            let rest = match self.number_drag {
+                Some((drag, rest)) if drag == id && !input.mouse_pressed.is_left() => rest,
+                _ => 0.,
+            };
+            let delta = rest + input.mouse_delta.x as f64 * step as f64;
+            let moved = value.offset(delta);
+            self.number_drag = Some((id, delta - (moved.to_f64() - value.to_f64())));
+            *value = moved;
         }
         if *value != last {
             res |= ResourceState::CHANGE;
         }
         self.draw_control_frame(id, base, ControlColor::Base, opt);
-        let mut buff = String::new();
-        buff.push_str(format!("{:.*}", precision, value).as_str());
-        self.draw_control_text(buff.as_str(), base, ControlColor::Text, opt);
+        self.draw_control_text(value.format(precision).as_str(), base, ControlColor::Text, opt);
         return res;
     }
+
+    // the x and y of the vector side by side in the next cell, each dragged like `number_ex`. The fields are keyed by
+    // `name`, it isn't drawn.
+    pub fn number_vec2_ex<T: Numeric>(&mut self, name: &str, value: &mut Vector2<T>, step: Real, precision: usize, opt: WidgetOption) -> ResourceState {
+        let id = self.idmngr.get_id_from_str(name);
+        let mut v = [value.x, value.y];
+        let res = self.number_components(id, &mut v, step, precision, opt);
+        [value.x, value.y] = v;
+        res
+    }
+
+    pub fn number_vec3_ex<T: Numeric>(&mut self, name: &str, value: &mut Vector3<T>, step: Real, precision: usize, opt: WidgetOption) -> ResourceState {
+        let id = self.idmngr.get_id_from_str(name);
+        let mut v = [value.x, value.y, value.z];
+        let res = self.number_components(id, &mut v, step, precision, opt);
+        [value.x, value.y, value.z] = v;
+        res
+    }
+
+    pub fn number_vec4_ex<T: Numeric>(&mut self, name: &str, value: &mut Vector4<T>, step: Real, precision: usize, opt: WidgetOption) -> ResourceState {
+        let id = self.idmngr.get_id_from_str(name);
+        let mut v = [value.x, value.y, value.z, value.w];
+        let res = self.number_components(id, &mut v, step, precision, opt);
+        [value.x, value.y, value.z, value.w] = v;
+        res
+    }
+
+    // splits the next cell into a number field per component, each named by a dimmed letter
+    fn number_components<T: Numeric>(&mut self, id: Id, values: &mut [T], step: Real, precision: usize, opt: WidgetOption) -> ResourceState {
+        const NAMES: [&str; 4] = ["x", "y", "z", "w"];
+        let mut res = ResourceState::NONE;
+        let r = self.layout.next();
+        let spacing = self.style.spacing;
+        let count = values.len() as i32;
+        let width = (r.width - spacing * (count - 1)) / count;
+        for (i, value) in values.iter_mut().enumerate() {
+            let x = r.x + i as i32 * (width + spacing);
+            // the last field takes the pixels left over by the division
+            let w = if i as i32 == count - 1 { r.x + r.width - x } else { width };
+            let field = rect(x, r.y, w, r.height);
+            res |= self.number_raw(value, IdManager::get_sub_id(id, NAMES[i]), field, step, precision, opt);
+            self.draw_field_hint(NAMES[i], field);
+        }
+        res
+    }
+
+    // a short name right aligned and dimmed inside a field
+    pub(crate) fn draw_field_hint(&mut self, name: &str, r: Recti) {
+        let font = self.style.font;
+        let tsize = self.atlas.get_text_size(font, name);
+        let pos = vec2(r.x + r.width - self.style.padding - tsize.width, r.y + (r.height - tsize.height) / 2);
+        let color = self.dimmed_text_color();
+        self.push_clip_rect(r);
+        self.draw_text(font, name, pos, color);
+        self.pop_clip_rect();
+    }
+}
+
+// the menus declared inside `Container::menu_bar`, laid out from left to right
+pub struct MenuBar<'a, PR> {
+    container: &'a mut Container<PR>,
+    id: Id,
+    bar: Recti,
+    x: i32,
+    titles: Vec<(Id, Recti)>,
+}
+
+impl<'a, PR: Clone> MenuBar<'a, PR> {
+    // a title opening the menu laid out by `f` with the menu items, separators and submenus
+    pub fn menu<F: FnOnce(&mut Container<PR>)>(&mut self, label: &str, f: F) {
+        let c = &mut *self.container;
+        let id = IdManager::get_sub_id(self.id, label);
+        let width = c.atlas.get_text_size(c.style.font, label).width + c.style.padding * 2;
+        let r = rect(self.x, self.bar.y, width, self.bar.height);
+        self.x += width;
+        self.titles.push((id, r));
+        c.menu_title(id, label, r, f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "builder")]
+    #[test]
+    fn toggle_keeps_its_id_when_the_state_moves() {
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 10, 300, 300));
+        // the state lives in a new box every frame, the old ones stay alive so the address always changes
+        let mut boxes = vec![Box::new(false)];
+        ctx.input.borrow_mut().mousemove(60, 39);
+        for step in 0..5 {
+            match step {
+                2 => ctx.input.borrow_mut().mousedown(60, 39, MouseButton::LEFT),
+                3 => ctx.input.borrow_mut().mouseup(60, 39, MouseButton::LEFT),
+                _ => {}
+            }
+            let mut state = Box::new(**boxes.last().unwrap());
+            ctx.frame(|ctx| {
+                ctx.window(&mut window, WidgetOption::NONE, |c| {
+                    c.set_row_widths_height(&[-1], 0);
+                    c.toggle("Dark", &mut state);
+                });
+            });
+            boxes.push(state);
+        }
+        assert!(**boxes.last().unwrap());
+    }
+
+    #[cfg(feature = "builder")]
+    #[test]
+    fn slow_drag_adds_up_a_fractional_step() {
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 10, 300, 300));
+        let mut value = Vector2::new(0i32, 0);
+        ctx.input.borrow_mut().mousemove(60, 39);
+        for step in 0..11 {
+            match step {
+                2 => ctx.input.borrow_mut().mousedown(60, 39, MouseButton::LEFT),
+                // a pixel a frame at a quarter of a unit per pixel
+                3..=10 => ctx.input.borrow_mut().mousemove(60 + step - 2, 39),
+                _ => {}
+            }
+            // a copy every frame, the fields are keyed by their name and not by where the vector lives
+            let mut copy = value;
+            ctx.frame(|ctx| {
+                ctx.window(&mut window, WidgetOption::NONE, |c| {
+                    c.set_row_widths_height(&[-1], 0);
+                    c.number_vec2_ex("position", &mut copy, 0.25, 0, WidgetOption::NONE);
+                });
+            });
+            value = copy;
+        }
+        assert_eq!((value.x, value.y), (2, 0));
+    }
 }
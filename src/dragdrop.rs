@@ -0,0 +1,214 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+use super::*;
+use std::any::Any;
+
+// distance in pixels the mouse travels with the button held before a drag starts
+const DRAG_THRESHOLD: i32 = 4;
+
+#[derive(Default)]
+pub(crate) struct DragDrop {
+    payload: Option<Box<dyn Any>>,
+    label: String,
+    press_pos: Vec2i,
+    // the drag of the current press ended, it doesn't start again before the next press
+    ended: bool,
+}
+
+impl DragDrop {
+    pub(crate) fn is_active(&self) -> bool {
+        self.payload.is_some()
+    }
+
+    pub(crate) fn label(&self) -> &str {
+        self.label.as_str()
+    }
+
+    pub(crate) fn press(&mut self, pos: Vec2i) {
+        self.press_pos = pos;
+        self.ended = false;
+    }
+
+    pub(crate) fn can_start(&self, pos: Vec2i) -> bool {
+        !self.is_active() && !self.ended && ((pos.x - self.press_pos.x).abs() > DRAG_THRESHOLD || (pos.y - self.press_pos.y).abs() > DRAG_THRESHOLD)
+    }
+
+    pub(crate) fn start(&mut self, label: &str, payload: Box<dyn Any>) {
+        self.label.clear();
+        self.label.push_str(label);
+        self.payload = Some(payload);
+    }
+
+    pub(crate) fn holds<T: Any>(&self) -> bool {
+        self.payload.as_ref().is_some_and(|p| p.is::<T>())
+    }
+
+    pub(crate) fn take<T: Any>(&mut self) -> Option<T> {
+        if !self.holds::<T>() {
+            return None;
+        }
+        self.payload.take().and_then(|p| p.downcast::<T>().ok()).map(|p| *p)
+    }
+
+    pub(crate) fn cancel(&mut self) {
+        self.payload = None;
+        self.ended = true;
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+
+    // what a frame saw: the drag started, the number target took a payload, the word target took one
+    #[derive(Debug, Default, PartialEq)]
+    struct Seen {
+        started: bool,
+        number: Option<i32>,
+        word: Option<String>,
+    }
+
+    // a window with a source dragging the number 7 above a target for numbers and one for words, each a row high
+    struct Fixture {
+        ctx: testing::TestContext,
+        window: WindowHandle<()>,
+        rects: [Recti; 3],
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let mut ctx = testing::context();
+            let window = ctx.new_window("window", rect(10, 10, 300, 300));
+            let mut fixture = Self {
+                ctx,
+                window,
+                rects: [Recti::default(); 3],
+            };
+            fixture.frame();
+            fixture
+        }
+
+        fn frame(&mut self) -> Seen {
+            let mut seen = Seen::default();
+            let rects = &mut self.rects;
+            self.ctx.frame(|ctx| {
+                ctx.window(&mut self.window, WidgetOption::NONE, |c| {
+                    c.set_row_widths_height(&[-1], 0);
+                    c.button_ex("Source", None, WidgetOption::NONE);
+                    rects[0] = c.layout.last_rect;
+                    seen.started = c.drag_source("seven", || 7i32);
+                    c.button_ex("Numbers", None, WidgetOption::NONE);
+                    rects[1] = c.layout.last_rect;
+                    seen.number = c.drop_target::<i32>();
+                    c.button_ex("Words", None, WidgetOption::NONE);
+                    rects[2] = c.layout.last_rect;
+                    seen.word = c.drop_target::<String>();
+                });
+            });
+            seen
+        }
+
+        fn center(&self, index: usize) -> Vec2i {
+            let r = self.rects[index];
+            vec2(r.x + r.width / 2, r.y + r.height / 2)
+        }
+
+        fn is_dragging(&self) -> bool {
+            self.ctx.shared.drag.borrow().is_active()
+        }
+
+        // presses over the source and drags past the threshold
+        fn start_drag(&mut self) {
+            let source = self.center(0);
+            self.ctx.input.borrow_mut().mousemove(source.x, source.y);
+            self.frame();
+            self.frame();
+            self.ctx.input.borrow_mut().mousedown(source.x, source.y, MouseButton::LEFT);
+            assert_eq!(self.frame(), Seen::default());
+            // a few pixels are still a click
+            self.ctx.input.borrow_mut().mousemove(source.x + DRAG_THRESHOLD, source.y);
+            assert_eq!(self.frame(), Seen::default());
+            assert!(!self.is_dragging());
+            self.ctx.input.borrow_mut().mousemove(source.x + DRAG_THRESHOLD + 1, source.y);
+            assert!(self.frame().started);
+            assert!(self.is_dragging());
+        }
+
+        fn release_at(&mut self, pos: Vec2i) -> Seen {
+            self.ctx.input.borrow_mut().mousemove(pos.x, pos.y);
+            assert_eq!(self.frame(), Seen::default());
+            self.ctx.input.borrow_mut().mouseup(pos.x, pos.y, MouseButton::LEFT);
+            self.frame()
+        }
+    }
+
+    #[test]
+    fn drop_on_a_target_of_the_payload_type() {
+        let mut fixture = Fixture::new();
+        fixture.start_drag();
+        let target = fixture.center(1);
+        assert_eq!(fixture.release_at(target), Seen { number: Some(7), ..Seen::default() });
+        assert!(!fixture.is_dragging());
+    }
+
+    #[test]
+    fn target_of_another_type_leaves_the_payload() {
+        let mut fixture = Fixture::new();
+        fixture.start_drag();
+        let target = fixture.center(2);
+        assert_eq!(fixture.release_at(target), Seen::default());
+        assert!(!fixture.is_dragging());
+    }
+
+    #[test]
+    fn release_over_nothing_cancels() {
+        let mut fixture = Fixture::new();
+        fixture.start_drag();
+        assert_eq!(fixture.release_at(vec2(200, 250)), Seen::default());
+        assert!(!fixture.is_dragging());
+        // and the next drag only starts after a new press
+        let target = fixture.center(1);
+        fixture.ctx.input.borrow_mut().mousemove(target.x, target.y);
+        assert_eq!(fixture.frame(), Seen::default());
+    }
+
+    #[test]
+    fn escape_cancels() {
+        let mut fixture = Fixture::new();
+        fixture.start_drag();
+        fixture.ctx.input.borrow_mut().keydown(Key::Escape);
+        fixture.frame();
+        assert!(!fixture.is_dragging());
+        // the button is still down over the target, it has nothing to take
+        let target = fixture.center(1);
+        fixture.ctx.input.borrow_mut().keyup(Key::Escape);
+        assert_eq!(fixture.release_at(target), Seen::default());
+    }
+}
@@ -121,6 +121,13 @@ impl IdManager {
         return res;
     }
 
+    // id of a value owned by another id, like the animations of a widget
+    pub fn get_sub_id(id: Id, s: &str) -> Id {
+        let mut res = id;
+        Self::hash_str(&mut res, s);
+        res
+    }
+
     pub fn get_id_from_str(&mut self, s: &str) -> Id {
         let mut res: Id = match self.id_stack.last() {
             Some(id) => *id,
@@ -167,6 +167,26 @@ impl LayoutManager {
         self.row_for_layout(height);
     }
 
+    // ends the rows laid out since `saved` was taken at `next_row` below the top of the body: the content reaches down
+    // to there and no further, and the next widget starts a new row
+    pub fn end_rows_at(&mut self, saved: &Layout, next_row: i32) {
+        let spacing = self.style.spacing;
+        self.item_index = self.current_row_widths.len();
+        let top = self.top_mut();
+        top.next_row = next_row;
+        top.max.y = max(saved.max.y, top.body.y + next_row - spacing);
+    }
+
+    // places the next widget `x` pixels right of the indent in the current row and `width` wide, whatever cell of the
+    // row comes next
+    pub fn cell_at(&mut self, x: i32, width: i32) {
+        self.current_row_widths.clear();
+        self.current_row_widths.push(width);
+        self.item_index = 0;
+        let top = self.top_mut();
+        top.position.x = top.indent + x;
+    }
+
     pub fn set_width(&mut self, width: i32) {
         self.top_mut().size.width = width;
     }
@@ -57,21 +57,49 @@ use std::{
     rc::Rc,
 };
 
+mod animation;
 mod atlas;
 mod canvas;
+mod color_picker;
 mod container;
+mod dragdrop;
 mod idmngr;
 mod layout;
+mod list;
+mod menu;
+mod numeric;
+mod record;
 mod rect_packer;
+mod scroll;
+mod shortcut;
+mod table;
+mod tabs;
+#[cfg(all(test, feature = "builder"))]
+mod testing;
+mod tooltip;
+mod touch;
 mod window;
 
 pub use atlas::*;
 pub use idmngr::*;
 pub use layout::*;
+pub use list::*;
+pub use numeric::*;
 pub use container::*;
 pub use window::*;
 pub use canvas::*;
+pub use color_picker::*;
+pub use record::*;
 pub use rect_packer::*;
+pub use shortcut::*;
+pub use table::*;
+pub use tabs::*;
+pub use touch::*;
+pub use animation::*;
+pub(crate) use dragdrop::*;
+pub(crate) use menu::*;
+pub(crate) use scroll::*;
+pub(crate) use tooltip::*;
 pub use rs_math3d::*;
 
 use bitflags::*;
@@ -96,7 +124,9 @@ pub enum Clip {
 #[derive(PartialEq, Copy, Clone)]
 #[repr(u32)]
 pub enum ControlColor {
-    Max = 14,
+    Max = 16,
+    FocusRing = 15,
+    Error = 14,
     ScrollThumb = 13,
     ScrollBase = 12,
     BaseFocus = 11,
@@ -135,6 +165,7 @@ impl ControlColor {
 
 bitflags! {
     pub struct ResourceState : u32 {
+        const DOUBLE_CLICK = 8;
         const CHANGE = 4;
         const SUBMIT = 2;
         const ACTIVE = 1;
@@ -143,6 +174,9 @@ bitflags! {
 }
 
 impl ResourceState {
+    pub fn is_double_clicked(&self) -> bool {
+        self.intersects(Self::DOUBLE_CLICK)
+    }
     pub fn is_changed(&self) -> bool {
         self.intersects(Self::CHANGE)
     }
@@ -160,6 +194,10 @@ impl ResourceState {
 bitflags! {
     #[derive(Copy, Clone)]
     pub struct WidgetOption : u32 {
+        const MENU_BAR = 8192;
+        const DOUBLE_CLICK = 4096;
+        const NO_NAV = 2048;
+        const PASSWORD = 1024;
         const AUTO_SIZE = 512;
         const HOLD_FOCUS = 256;
         const NO_TITLE = 128;
@@ -196,7 +234,122 @@ impl NodeState {
     }
 }
 
+// the state of a tri-state checkbox, mixed stands for a parent whose children are partly checked
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CheckState {
+    #[default]
+    Unchecked,
+    Checked,
+    Mixed,
+}
+
+impl CheckState {
+    pub fn is_checked(&self) -> bool {
+        matches!(self, Self::Checked)
+    }
+
+    pub fn is_mixed(&self) -> bool {
+        matches!(self, Self::Mixed)
+    }
+
+    // checked when all of the states are, unchecked when none is or there are none, mixed otherwise
+    pub fn from_children<I: IntoIterator<Item = bool>>(children: I) -> Self {
+        let (mut any, mut all) = (false, true);
+        for checked in children {
+            any |= checked;
+            all &= checked;
+        }
+        match (any, all) {
+            (false, _) => Self::Unchecked,
+            (true, true) => Self::Checked,
+            (true, false) => Self::Mixed,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CursorIcon {
+    #[default]
+    Arrow,
+    IBeam,
+    ResizeNS,
+    ResizeEW,
+    ResizeNWSE,
+    Hand,
+    Move,
+    NotAllowed,
+}
+
+// how a modal dialog was closed, escape and the close button cancel it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DialogResult {
+    Ok,
+    Cancel,
+    Custom(u32),
+}
+
+// the screen behind an open modal is dimmed with this color
+const MODAL_DIM: Color = Color { r: 0, g: 0, b: 0, a: 128 };
+
+#[derive(Clone, Copy)]
+pub enum TextFilter<'a> {
+    Any,
+    Digits,
+    Hex,
+    Custom(&'a dyn Fn(char) -> bool),
+}
+
+impl<'a> TextFilter<'a> {
+    pub fn accepts(&self, c: char) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Digits => c.is_ascii_digit(),
+            Self::Hex => c.is_ascii_hexdigit(),
+            Self::Custom(f) => f(c),
+        }
+    }
+}
+
+// constraints applied to a text field: which characters get in, how many, and whether the content can be submitted
+#[derive(Clone, Copy)]
+pub struct TextField<'a> {
+    pub filter: TextFilter<'a>,
+    pub max_length: Option<usize>,
+    pub validate: Option<&'a dyn Fn(&str) -> bool>,
+}
+
+impl<'a> Default for TextField<'a> {
+    fn default() -> Self {
+        Self {
+            filter: TextFilter::Any,
+            max_length: None,
+            validate: None,
+        }
+    }
+}
+
+impl<'a> TextField<'a> {
+    pub fn is_valid(&self, text: &str) -> bool {
+        match self.validate {
+            Some(validate) => validate(text),
+            None => true,
+        }
+    }
+}
+
 impl WidgetOption {
+    pub fn has_menu_bar(&self) -> bool {
+        self.intersects(WidgetOption::MENU_BAR)
+    }
+    pub fn is_double_click(&self) -> bool {
+        self.intersects(WidgetOption::DOUBLE_CLICK)
+    }
+    pub fn has_no_nav(&self) -> bool {
+        self.intersects(WidgetOption::NO_NAV)
+    }
+    pub fn is_password(&self) -> bool {
+        self.intersects(WidgetOption::PASSWORD)
+    }
     pub fn is_auto_sizing(&self) -> bool {
         self.intersects(WidgetOption::AUTO_SIZE)
     }
@@ -233,7 +386,7 @@ impl WidgetOption {
 }
 
 bitflags! {
-    #[derive(Copy, Clone, Debug)]
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
     pub struct MouseButton : u32 {
         const MIDDLE = 4;
         const RIGHT = 2;
@@ -257,6 +410,51 @@ impl MouseButton {
     }
 }
 
+bitflags! {
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct GamepadButton : u32 {
+        const RIGHT_SHOULDER = 128;
+        const LEFT_SHOULDER = 64;
+        const CANCEL = 32;
+        const ACCEPT = 16;
+        const RIGHT = 8;
+        const LEFT = 4;
+        const DOWN = 2;
+        const UP = 1;
+        const NONE = 0;
+    }
+}
+
+impl GamepadButton {
+    pub fn is_right_shoulder(&self) -> bool {
+        self.intersects(Self::RIGHT_SHOULDER)
+    }
+    pub fn is_left_shoulder(&self) -> bool {
+        self.intersects(Self::LEFT_SHOULDER)
+    }
+    pub fn is_cancel(&self) -> bool {
+        self.intersects(Self::CANCEL)
+    }
+    pub fn is_accept(&self) -> bool {
+        self.intersects(Self::ACCEPT)
+    }
+    pub fn is_right(&self) -> bool {
+        self.intersects(Self::RIGHT)
+    }
+    pub fn is_left(&self) -> bool {
+        self.intersects(Self::LEFT)
+    }
+    pub fn is_down(&self) -> bool {
+        self.intersects(Self::DOWN)
+    }
+    pub fn is_up(&self) -> bool {
+        self.intersects(Self::UP)
+    }
+    pub fn is_none(&self) -> bool {
+        self.bits() == 0
+    }
+}
+
 bitflags! {
     #[derive(Copy, Clone, Debug)]
     pub struct KeyMode : u32 {
@@ -290,6 +488,108 @@ impl KeyMode {
     }
 }
 
+// the enum, its list and the name of every key come from the one list of variants so they can't drift apart
+macro_rules! keys {
+    ($($key:ident),* $(,)?) => {
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        #[repr(u8)]
+        pub enum Key {
+            $($key,)*
+        }
+
+        impl Key {
+            pub const ALL: &'static [Key] = &[$(Key::$key,)*];
+
+            // the variant name, as written in input recordings
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Key::$key => stringify!($key),)*
+                }
+            }
+
+            pub fn from_name(name: &str) -> Option<Key> {
+                match name {
+                    $(stringify!($key) => Some(Key::$key),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+keys! {
+    Shift, Ctrl, Alt, Backspace, Return, Tab, Escape, Space, Delete, Insert, Home, End, PageUp, PageDown, Left, Right, Up, Down,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    Num0, Num1, Num2, Num3, Num4, Num5, Num6, Num7, Num8, Num9,
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+}
+
+// a key is a bit of `KeySet`
+const _: () = assert!(Key::ALL.len() <= 128);
+
+impl Key {
+    pub fn mode(&self) -> KeyMode {
+        match self {
+            Self::Shift => KeyMode::SHIFT,
+            Self::Ctrl => KeyMode::CTRL,
+            Self::Alt => KeyMode::ALT,
+            Self::Backspace => KeyMode::BACKSPACE,
+            Self::Return => KeyMode::RETURN,
+            _ => KeyMode::NONE,
+        }
+    }
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct KeySet(u128);
+
+impl KeySet {
+    pub fn contains(&self, key: Key) -> bool {
+        self.0 & (1 << key as u8) != 0
+    }
+    pub fn insert(&mut self, key: Key) {
+        self.0 |= 1 << key as u8
+    }
+    pub fn remove(&mut self, key: Key) {
+        self.0 &= !(1 << key as u8)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+    pub fn clear(&mut self) {
+        self.0 = 0
+    }
+}
+
+// how close in time (milliseconds) and space (pixels) presses must be to count as one multi click, and how long a
+// button or finger has to stay put to long press
+#[derive(Clone, Copy, Debug)]
+pub struct ClickThresholds {
+    pub double_click_ms: u64,
+    pub long_press_ms: u64,
+    pub slop: i32,
+}
+
+impl Default for ClickThresholds {
+    fn default() -> Self {
+        Self {
+            double_click_ms: 400,
+            long_press_ms: 500,
+            slop: 4,
+        }
+    }
+}
+
+#[derive(Default, Copy, Clone, Debug)]
+struct ClickState {
+    count: usize,
+    time: u64,
+    pos: Vec2i,
+    long_pressed: bool,
+}
+
+const CLICK_BUTTONS: [MouseButton; 3] = [MouseButton::LEFT, MouseButton::RIGHT, MouseButton::MIDDLE];
+
 #[derive(Clone, Debug)]
 pub struct Input {
     mouse_pos: Vec2i,
@@ -298,9 +598,25 @@ pub struct Input {
     scroll_delta: Vec2i,
     mouse_down: MouseButton,
     mouse_pressed: MouseButton,
+    gamepad_down: GamepadButton,
+    gamepad_pressed: GamepadButton,
     key_down: KeyMode,
     key_pressed: KeyMode,
+    keys_down: KeySet,
+    keys_pressed: KeySet,
+    keys_released: KeySet,
+    keys_repeated: KeySet,
     input_text: String,
+    composition_text: String,
+    composition_cursor: (usize, usize),
+    time: u64,
+    last_time: Option<u64>,
+    delta_time: u64,
+    thresholds: ClickThresholds,
+    clicks: [ClickState; 3],
+    mouse_long_pressed: MouseButton,
+    touch: TouchState,
+    recorder: Option<Recorder>,
 }
 
 impl Default for Input {
@@ -312,64 +628,230 @@ impl Default for Input {
             scroll_delta: Vec2i::default(),
             mouse_down: MouseButton::NONE,
             mouse_pressed: MouseButton::NONE,
+            gamepad_down: GamepadButton::NONE,
+            gamepad_pressed: GamepadButton::NONE,
             key_down: KeyMode::NONE,
             key_pressed: KeyMode::NONE,
+            keys_down: KeySet::default(),
+            keys_pressed: KeySet::default(),
+            keys_released: KeySet::default(),
+            keys_repeated: KeySet::default(),
             input_text: String::default(),
+            composition_text: String::default(),
+            composition_cursor: (0, 0),
+            time: 0,
+            last_time: None,
+            delta_time: 0,
+            thresholds: ClickThresholds::default(),
+            clicks: [ClickState::default(); 3],
+            mouse_long_pressed: MouseButton::NONE,
+            touch: TouchState::default(),
+            recorder: None,
         }
     }
 }
 
 impl Input {
+    // host clock in milliseconds, set once per frame; time based detection such as long presses relies on it
+    pub fn set_time(&mut self, ms: u64) {
+        self.record(InputEvent::Time(ms));
+        self.time = ms;
+    }
+
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    // milliseconds elapsed since the previous frame
+    pub fn delta_time(&self) -> u64 {
+        self.delta_time
+    }
+
+    pub fn set_click_thresholds(&mut self, thresholds: ClickThresholds) {
+        self.thresholds = thresholds;
+    }
+
+    pub fn click_thresholds(&self) -> ClickThresholds {
+        self.thresholds
+    }
+
+    // number of presses in quick succession of the button, the current press included
+    pub fn click_count(&self, btn: MouseButton) -> usize {
+        match CLICK_BUTTONS.iter().position(|b| btn.contains(*b)) {
+            Some(i) => self.clicks[i].count,
+            None => 0,
+        }
+    }
+
+    pub fn is_double_clicked(&self, btn: MouseButton) -> bool {
+        self.mouse_pressed.intersects(btn) && self.click_count(btn) == 2
+    }
+
+    // the button has been held down without moving for `long_press_ms`, reported once per press
+    pub fn is_long_pressed(&self, btn: MouseButton) -> bool {
+        self.mouse_long_pressed.intersects(btn)
+    }
+
     pub fn mousemove(&mut self, x: i32, y: i32) {
+        self.record(InputEvent::MouseMove(x, y));
         self.mouse_pos = vec2(x, y);
     }
 
     pub fn mousedown(&mut self, x: i32, y: i32, btn: MouseButton) {
-        self.mousemove(x, y);
+        self.record(InputEvent::MouseDown(x, y, btn));
+        self.mouse_pos = vec2(x, y);
         self.mouse_down |= btn;
         self.mouse_pressed |= btn;
+
+        let (time, thresholds) = (self.time, self.thresholds);
+        for (b, click) in CLICK_BUTTONS.iter().zip(self.clicks.iter_mut()) {
+            if !btn.contains(*b) {
+                continue;
+            }
+            let near = (x - click.pos.x).abs() <= thresholds.slop && (y - click.pos.y).abs() <= thresholds.slop;
+            let quick = click.count > 0 && time.saturating_sub(click.time) <= thresholds.double_click_ms;
+            click.count = if near && quick { click.count + 1 } else { 1 };
+            click.time = time;
+            click.pos = vec2(x, y);
+            click.long_pressed = false;
+        }
     }
 
     pub fn mouseup(&mut self, x: i32, y: i32, btn: MouseButton) {
-        self.mousemove(x, y);
+        self.record(InputEvent::MouseUp(x, y, btn));
+        self.mouse_pos = vec2(x, y);
         self.mouse_down &= !btn;
     }
 
+    pub fn gamepaddown(&mut self, btn: GamepadButton) {
+        self.record(InputEvent::GamepadDown(btn));
+        self.gamepad_down |= btn;
+        self.gamepad_pressed |= btn;
+    }
+
+    pub fn gamepadup(&mut self, btn: GamepadButton) {
+        self.record(InputEvent::GamepadUp(btn));
+        self.gamepad_down &= !btn;
+    }
+
+    pub fn gamepad_down(&self) -> GamepadButton {
+        self.gamepad_down
+    }
+
+    pub fn gamepad_pressed(&self) -> GamepadButton {
+        self.gamepad_pressed
+    }
+
     pub fn scroll(&mut self, x: i32, y: i32) {
+        self.record(InputEvent::Scroll(x, y));
         self.scroll_delta.x += x;
         self.scroll_delta.y += y;
     }
 
-    pub fn keydown(&mut self, key: KeyMode) {
-        self.key_pressed |= key;
-        self.key_down |= key;
+    // a keydown for a key that is already down is an auto-repeat: it counts as pressed again
+    pub fn keydown(&mut self, key: Key) {
+        self.record(InputEvent::KeyDown(key));
+        if self.keys_down.contains(key) {
+            self.keys_repeated.insert(key);
+        }
+        self.keys_pressed.insert(key);
+        self.keys_down.insert(key);
+        self.key_pressed |= key.mode();
+        self.key_down |= key.mode();
+    }
+
+    pub fn keyup(&mut self, key: Key) {
+        self.record(InputEvent::KeyUp(key));
+        self.keys_down.remove(key);
+        self.keys_released.insert(key);
+        self.key_down &= !key.mode();
+    }
+
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.keys_down.contains(key)
+    }
+
+    pub fn is_key_pressed(&self, key: Key) -> bool {
+        self.keys_pressed.contains(key)
+    }
+
+    pub fn is_key_released(&self, key: Key) -> bool {
+        self.keys_released.contains(key)
     }
 
-    pub fn keyup(&mut self, key: KeyMode) {
-        self.key_down &= !key;
+    pub fn is_key_repeated(&self, key: Key) -> bool {
+        self.keys_repeated.contains(key)
+    }
+
+    pub fn key_mode(&self) -> KeyMode {
+        self.key_down
     }
 
     pub fn text(&mut self, text: &str) {
+        self.record(InputEvent::Text(text.to_string()));
         for c in text.chars() {
             self.input_text.push(c);
         }
     }
 
+    // in-progress (preedit) text from an input method: `start` and `length` are in chars and
+    // select the segment being converted. An empty `text` ends the composition.
+    pub fn composition(&mut self, text: &str, start: usize, length: usize) {
+        self.record(InputEvent::Composition(text.to_string(), start, length));
+        self.composition_text.clear();
+        self.composition_text.push_str(text);
+        let count = self.composition_text.chars().count();
+        let start = min(start, count);
+        self.composition_cursor = (start, min(length, count - start));
+    }
+
+    pub fn composition_text(&self) -> &str {
+        self.composition_text.as_str()
+    }
+
+    pub fn composition_cursor(&self) -> (usize, usize) {
+        self.composition_cursor
+    }
+
+    pub fn is_composing(&self) -> bool {
+        !self.composition_text.is_empty()
+    }
+
     fn prelude(&mut self) {
+        self.record_frame();
+        self.delta_time = self.last_time.map_or(0, |last| self.time.saturating_sub(last));
+        self.last_time = Some(self.time);
+
+        let (time, thresholds, mouse_pos) = (self.time, self.thresholds, self.mouse_pos);
+        for (b, click) in CLICK_BUTTONS.iter().zip(self.clicks.iter_mut()) {
+            let still = (mouse_pos.x - click.pos.x).abs() <= thresholds.slop && (mouse_pos.y - click.pos.y).abs() <= thresholds.slop;
+            if self.mouse_down.contains(*b) && !click.long_pressed && still && time.saturating_sub(click.time) >= thresholds.long_press_ms {
+                click.long_pressed = true;
+                self.mouse_long_pressed |= *b;
+            }
+        }
+        self.synthesize(|input| input.touch_prelude());
         self.mouse_delta.x = self.mouse_pos.x - self.last_mouse_pos.x;
         self.mouse_delta.y = self.mouse_pos.y - self.last_mouse_pos.y;
     }
 
     fn epilogue(&mut self) {
         self.key_pressed = KeyMode::NONE;
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.keys_repeated.clear();
         self.input_text.clear();
         self.mouse_pressed = MouseButton::NONE;
+        self.mouse_long_pressed = MouseButton::NONE;
+        self.gamepad_pressed = GamepadButton::NONE;
         self.scroll_delta = vec2(0, 0);
+        self.touch_epilogue();
         self.last_mouse_pos = self.mouse_pos;
+        self.start_waiting_recording();
     }
 }
 
-#[derive(Default, Copy, Clone)]
+#[derive(Default, Copy, Clone, PartialEq, Eq)]
 #[repr(C)]
 pub struct Color {
     pub r: u8,
@@ -394,7 +876,7 @@ pub struct Style {
     pub title_height: i32,
     pub scrollbar_size: i32,
     pub thumb_size: i32,
-    pub colors: [Color; 14],
+    pub colors: [Color; 16],
 }
 
 pub type Real = f32;
@@ -432,6 +914,8 @@ impl Default for Style {
                 Color { r: 40, g: 40, b: 40, a: 255 },
                 Color { r: 43, g: 43, b: 43, a: 255 },
                 Color { r: 30, g: 30, b: 30, a: 255 },
+                Color { r: 200, g: 60, b: 60, a: 255 },
+                Color { r: 90, g: 150, b: 230, a: 255 },
             ],
         }
     }
@@ -449,6 +933,12 @@ pub fn color(r: u8, g: u8, b: u8, a: u8) -> Color {
     Color { r, g, b, a }
 }
 
+// linear blend from `a` (t = 0) to `b` (t = 1)
+pub fn mix_color(a: Color, b: Color, t: Real) -> Color {
+    let mix = |x: u8, y: u8| (x as Real + (y as Real - x as Real) * t.clamp(0.0, 1.0)).round() as u8;
+    color(mix(a.r, b.r), mix(a.g, b.g), mix(a.b, b.b), mix(a.a, b.a))
+}
+
 pub fn expand_rect(r: Recti, n: i32) -> Recti {
     rect(r.x - n, r.y - n, r.width + n * 2, r.height + n * 2)
 }
@@ -474,6 +964,25 @@ impl<PR: Clone> ContainerHandle<PR> {
     }
 }
 
+// context wide state handed to every container
+pub(crate) struct Shared<PR> {
+    pub(crate) drag: Rc<RefCell<DragDrop>>,
+    pub(crate) tooltip: Rc<RefCell<Tooltip<PR>>>,
+    pub(crate) menu: Rc<RefCell<ContextMenu<PR>>>,
+    pub(crate) shortcuts: Rc<RefCell<Shortcuts>>,
+}
+
+impl<PR> Clone for Shared<PR> {
+    fn clone(&self) -> Self {
+        Self {
+            drag: self.drag.clone(),
+            tooltip: self.tooltip.clone(),
+            menu: self.menu.clone(),
+            shortcuts: self.shortcuts.clone(),
+        }
+    }
+}
+
 pub struct Context<PR, R: Renderer<PR>> {
     canvas: Canvas<PR, R>,
     style: Style,
@@ -483,8 +992,14 @@ pub struct Context<PR, R: Renderer<PR>> {
     hover_root: Option<WindowHandle<PR>>,
     next_hover_root: Option<WindowHandle<PR>>,
     scroll_target: Option<WindowHandle<PR>>,
+    nav_root: Option<WindowHandle<PR>>,
 
     root_list: Vec<WindowHandle<PR>>,
+    // open modals in the order they were opened, with the number of times each was drawn since
+    modals: Vec<(WindowHandle<PR>, usize)>,
+    main_menu: Option<WindowHandle<PR>>,
+    screen: Dimensioni,
+    shared: Shared<PR>,
 
     pub input: Rc<RefCell<Input>>,
 }
@@ -499,33 +1014,85 @@ impl<PR, R: Renderer<PR>> Context<PR, R> {
             hover_root: None,
             next_hover_root: None,
             scroll_target: None,
+            nav_root: None,
 
             root_list: Vec::default(),
+            modals: Vec::default(),
+            main_menu: None,
+            screen: dim,
+            shared: Shared {
+                drag: Rc::new(RefCell::new(DragDrop::default())),
+                tooltip: Rc::new(RefCell::new(Tooltip::new(dim))),
+                menu: Rc::new(RefCell::new(ContextMenu::new(dim))),
+                shortcuts: Rc::new(RefCell::new(Shortcuts::default())),
+            },
 
             input: Rc::new(RefCell::new(Input::default())),
         }
     }
 }
 
+impl<PR, R: Renderer<PR>> Drop for Context<PR, R> {
+    fn drop(&mut self) {
+        // the tooltip and menu windows' containers hold their state as well
+        self.shared.tooltip.borrow_mut().window = None;
+        self.shared.menu.borrow_mut().windows.clear();
+    }
+}
+
 impl<PR: Clone, R: Renderer<PR>> Context<PR, R> {
     pub fn clear(&mut self, width: i32, height: i32, clr: Color) {
         self.canvas.clear(width, height, clr);
+        self.screen = Dimensioni::new(width, height);
+        self.shared.tooltip.borrow_mut().screen = Dimensioni::new(width, height);
+        self.shared.menu.borrow_mut().screen = Dimensioni::new(width, height);
     }
 
     pub fn flush(&mut self) {
         for r in &self.root_list {
             r.render(&mut self.canvas);
         }
+        self.draw_drag_preview();
         self.canvas.flush()
     }
 
+    // the dragged payload's label follows the mouse on top of every window
+    fn draw_drag_preview(&mut self) {
+        let drag = self.shared.drag.borrow();
+        if !drag.is_active() {
+            return;
+        }
+        let font = self.style.font;
+        let padding = self.style.padding;
+        let mouse_pos = self.input.borrow().mouse_pos;
+        let tsize = self.canvas.get_atlas().get_text_size(font, drag.label());
+        let r = rect(mouse_pos.x + 12, mouse_pos.y + 12, tsize.width + padding * 2, tsize.height + padding * 2);
+        self.canvas.set_clip_rect(UNCLIPPED_RECT);
+        self.canvas.draw_rect(expand_rect(r, 1), self.style.colors[ControlColor::Border as usize]);
+        self.canvas.draw_rect(r, self.style.colors[ControlColor::Button as usize]);
+        self.canvas.draw_chars(
+            font,
+            drag.label(),
+            vec2(r.x + padding, r.y + padding),
+            self.style.colors[ControlColor::Text as usize],
+        );
+    }
+
     #[inline(never)]
     fn frame_begin(&mut self) {
         self.scroll_target = None;
         self.input.borrow_mut().prelude();
+        self.shared.shortcuts.borrow_mut().update(&self.input.borrow());
+        if self.input.borrow().mouse_pressed.is_left() {
+            self.shared.drag.borrow_mut().press(self.input.borrow().mouse_pos);
+        }
         for r in &mut self.root_list {
             r.prepare();
         }
+        if self.shared.tooltip.borrow().window.is_none() {
+            let window = self.new_popup("!tooltip");
+            self.shared.tooltip.borrow_mut().window = Some(window);
+        }
         self.frame += 1;
         self.root_list.clear();
     }
@@ -536,6 +1103,32 @@ impl<PR: Clone, R: Renderer<PR>> Context<PR, R> {
             r.finish();
         }
 
+        // the top most open modal takes the hover and the keyboard from every window below it
+        self.modals.retain(|(w, _)| w.is_open());
+        let modal = self.modals.iter().rev().map(|(w, _)| w).find(|w| self.root_list.contains(w)).cloned();
+        if let Some(modal) = &modal {
+            let mouse_pos = self.input.borrow().mouse_pos;
+            let over = modal.inner().main.rect.contains(&mouse_pos);
+            self.next_hover_root = if over { Some(modal.clone()) } else { None };
+            for r in &mut self.root_list {
+                if r != modal {
+                    r.inner_mut().main.blur();
+                }
+            }
+        }
+
+        // open context menus take the hover from the windows below them
+        let menus = self.shared.menu.borrow().visible_windows();
+        for window in &menus {
+            let mut window = window.clone();
+            window.finish();
+            self.root_list.push(window);
+        }
+        let mouse_pos = self.input.borrow().mouse_pos;
+        if let Some(window) = self.shared.menu.borrow().is_over(mouse_pos) {
+            self.next_hover_root = Some(window);
+        }
+
         let mouse_pressed = self.input.borrow().mouse_pressed;
         match (mouse_pressed.is_none(), &self.next_hover_root) {
             (false, Some(next_hover_root)) if next_hover_root.zindex() < self.last_zindex && next_hover_root.zindex() >= 0 => {
@@ -544,6 +1137,67 @@ impl<PR: Clone, R: Renderer<PR>> Context<PR, R> {
             _ => (),
         }
 
+        // keyboard navigation goes to the window picked with the shoulder buttons until the mouse is used again,
+        // otherwise to the hovered window, or the top most one when the mouse is elsewhere
+        let gamepad = self.input.borrow().gamepad_pressed();
+        if modal.is_none() && (gamepad.is_right_shoulder() || gamepad.is_left_shoulder()) {
+            self.cycle_windows(gamepad.is_right_shoulder());
+        }
+        if !mouse_pressed.is_none() || !self.nav_root.as_ref().is_some_and(|w| self.root_list.contains(w)) {
+            self.nav_root = None;
+        }
+        let nav_window = modal
+            .clone()
+            .or_else(|| self.nav_root.clone())
+            .or_else(|| self.next_hover_root.clone())
+            .or_else(|| self.root_list.iter().max_by_key(|w| w.zindex()).cloned());
+        for r in &self.root_list {
+            r.inner().main.nav.borrow_mut().active = false;
+        }
+        if let Some(window) = nav_window {
+            let window = window.inner();
+            let mut nav = window.main.nav.borrow_mut();
+            nav.active = true;
+            nav.navigate(&self.input.borrow());
+        }
+
+        // a drag nobody accepted ends when the button is released, escape cancels it
+        if !self.input.borrow().mouse_down.is_left() || self.input.borrow().is_key_pressed(Key::Escape) {
+            self.shared.drag.borrow_mut().cancel();
+        }
+
+        // modals go over every window, menus over them and the tooltip over everything without taking part in hovering
+        // or navigation
+        let mut zindex = self.last_zindex;
+        for (window, _) in &self.modals {
+            zindex += 1;
+            window.clone().inner_mut().main.zindex = zindex;
+        }
+        for window in &menus {
+            zindex += 1;
+            window.clone().inner_mut().main.zindex = zindex;
+        }
+        self.shared.menu.borrow_mut().end_frame(&self.input.borrow());
+        let tooltip = self.shared.tooltip.borrow().visible_window();
+        if let Some(mut window) = tooltip {
+            window.inner_mut().main.zindex = zindex + 1;
+            self.root_list.push(window);
+        }
+        self.shared.tooltip.borrow_mut().end_frame();
+
+        // a focused text field swallows the plain key shortcuts of the next frame
+        let text_input = self.caret_rect().is_some();
+        self.shared.shortcuts.borrow_mut().text_input = text_input;
+
+        // the wheel moves the window the mouse is over, inside it the innermost container that can still move
+        let scroll_delta = self.input.borrow().scroll_delta;
+        if scroll_delta.x != 0 || scroll_delta.y != 0 {
+            self.scroll_target = self.hover_root.clone();
+        }
+        if let Some(window) = &mut self.scroll_target {
+            window.inner_mut().main.take_wheel(scroll_delta);
+        }
+
         self.input.borrow_mut().epilogue();
 
         // prepare the next frame
@@ -571,17 +1225,41 @@ impl<PR: Clone, R: Renderer<PR>> Context<PR, R> {
     }
 
     pub fn new_window(&mut self, name: &str, initial_rect: Recti) -> WindowHandle<PR> {
-        let mut window = WindowHandle::window(name, self.canvas.get_atlas(), &self.style, self.input.clone(), initial_rect);
+        let mut window = WindowHandle::window(
+            name,
+            self.canvas.get_atlas(),
+            &self.style,
+            self.input.clone(),
+            self.shared.clone(),
+            initial_rect,
+        );
         self.bring_to_front(&mut window);
         window
     }
 
     pub fn new_popup(&mut self, name: &str) -> WindowHandle<PR> {
-        WindowHandle::popup(name, self.canvas.get_atlas(), &self.style, self.input.clone())
+        WindowHandle::popup(name, self.canvas.get_atlas(), &self.style, self.input.clone(), self.shared.clone())
+    }
+
+    pub fn new_modal(&mut self, name: &str, initial_rect: Recti) -> WindowHandle<PR> {
+        WindowHandle::modal(
+            name,
+            self.canvas.get_atlas(),
+            &self.style,
+            self.input.clone(),
+            self.shared.clone(),
+            initial_rect,
+        )
     }
 
     pub fn new_panel(&mut self, name: &str) -> ContainerHandle<PR> {
-        ContainerHandle::new(Container::new(name, self.canvas.get_atlas(), &self.style, self.input.clone()))
+        ContainerHandle::new(Container::new(
+            name,
+            self.canvas.get_atlas(),
+            &self.style,
+            self.input.clone(),
+            self.shared.clone(),
+        ))
     }
 
     pub fn bring_to_front(&mut self, window: &mut WindowHandle<PR>) {
@@ -589,28 +1267,48 @@ impl<PR: Clone, R: Renderer<PR>> Context<PR, R> {
         window.inner_mut().main.zindex = self.last_zindex;
     }
 
+    // forward brings the bottom window to the front, backward sends the front window to the bottom
+    fn cycle_windows(&mut self, forward: bool) {
+        let mut windows: Vec<WindowHandle<PR>> = self
+            .root_list
+            .iter()
+            .filter(|w| !w.inner().is_popup() && !w.inner().is_modal())
+            .cloned()
+            .collect();
+        if windows.len() < 2 {
+            return;
+        }
+        windows.sort_by_key(|w| w.zindex());
+        let count = windows.len();
+        if forward {
+            self.bring_to_front(&mut windows[0]);
+            self.nav_root = Some(windows[0].clone());
+        } else {
+            for w in &mut windows[..count - 1] {
+                self.bring_to_front(w);
+            }
+            self.nav_root = Some(windows[count - 2].clone());
+        }
+    }
+
     #[inline(never)]
     fn begin_root_container(&mut self, window: &mut WindowHandle<PR>) {
         self.root_list.push(window.clone());
+        // the windows below an open modal are only drawn
+        let blocked = self.top_modal().is_some_and(|modal| modal != window);
+        window.inner_mut().main.set_blocked(blocked);
 
         if window.inner().main.rect.contains(&self.input.borrow().mouse_pos)
             && (self.next_hover_root.is_none() || window.zindex() > self.next_hover_root.as_ref().unwrap().zindex())
         {
             self.next_hover_root = Some(window.clone());
         }
-        let container = &mut window.inner_mut().main;
-        container.clip_stack.push(UNCLIPPED_RECT);
+        window.inner_mut().main.begin_root();
     }
 
     #[inline(never)]
     fn end_root_container(&mut self, window: &mut WindowHandle<PR>) {
-        let container = &mut window.inner_mut().main;
-        container.pop_clip_rect();
-
-        let layout = *container.layout.top();
-        container.content_size.x = layout.max.x - layout.body.x;
-        container.content_size.y = layout.max.y - layout.body.y;
-        container.layout.stack.pop();
+        window.inner_mut().main.end_root();
     }
 
     #[inline(never)]
@@ -633,13 +1331,34 @@ impl<PR: Clone, R: Renderer<PR>> Context<PR, R> {
 
     pub fn window<F: FnOnce(&mut Container<PR>)>(&mut self, window: &mut WindowHandle<PR>, opt: WidgetOption, f: F) {
         // call the window function if the window is open
+        window.inner_mut().main.style = self.style.clone();
         if self.begin_window(window, opt) {
-            window.inner_mut().main.style = self.style.clone();
             f(&mut window.inner_mut().main);
             self.end_window(window);
         }
     }
 
+    // a menu bar across the top of the screen, kept in front of the windows but below the modals
+    pub fn main_menu_bar<F: FnOnce(&mut MenuBar<PR>)>(&mut self, f: F) {
+        let mut window = match &self.main_menu {
+            Some(window) => window.clone(),
+            None => {
+                let atlas = self.canvas.get_atlas();
+                let window = WindowHandle::window("!menubar", atlas, &self.style, self.input.clone(), self.shared.clone(), Recti::default());
+                self.main_menu = Some(window.clone());
+                window
+            }
+        };
+        // an open modal and its dim layer stay over the bar
+        if self.top_modal().is_none() && window.inner().main.zindex < self.last_zindex {
+            self.bring_to_front(&mut window);
+        }
+        let height = self.style.default_cell_size.height + self.style.padding * 2;
+        window.inner_mut().main.rect = rect(0, 0, self.screen.width, height);
+        let opt = WidgetOption::NO_TITLE | WidgetOption::NO_RESIZE | WidgetOption::NO_SCROLL | WidgetOption::MENU_BAR;
+        self.window(&mut window, opt, |c| c.menu_bar(f));
+    }
+
     pub fn open_popup(&mut self, window: &mut WindowHandle<PR>) {
         self.next_hover_root = Some(window.clone());
         self.hover_root = self.next_hover_root.clone();
@@ -654,7 +1373,166 @@ impl<PR: Clone, R: Renderer<PR>> Context<PR, R> {
         self.window(window, opt, f);
     }
 
+    // opens the modal in the middle of the screen on top of the modals already open
+    pub fn open_modal(&mut self, window: &mut WindowHandle<PR>) {
+        let mut inner = window.inner_mut();
+        inner.activity = Activity::Open;
+        // nothing carries over from the last time it was open
+        inner.main.hover = None;
+        inner.main.blur();
+        drop(inner);
+        self.modals.retain(|(w, _)| w != window);
+        self.modals.push((window.clone(), 0));
+        self.bring_to_front(window);
+    }
+
+    // draws an open modal, `f` returns a result to close it. Returns how the modal was closed on the frame it closes.
+    pub fn modal<F: FnOnce(&mut Container<PR>) -> Option<DialogResult>>(
+        &mut self,
+        window: &mut WindowHandle<PR>,
+        opt: WidgetOption,
+        f: F,
+    ) -> Option<DialogResult> {
+        if !window.is_open() {
+            return None;
+        }
+        let drawn = match self.modals.iter_mut().find(|(w, _)| w == window) {
+            Some((_, drawn)) => {
+                *drawn += 1;
+                *drawn
+            }
+            None => {
+                self.modals.push((window.clone(), 1));
+                1
+            }
+        };
+        let is_top = self.modals.last().is_some_and(|(w, _)| w == window);
+        let screen = rect(0, 0, self.screen.width, self.screen.height);
+        // an auto sized modal stays hidden until its size settled, it needs one draw to measure the content and one
+        // more to take its size
+        let measuring = opt.is_auto_sizing() && drawn <= 2;
+        if drawn == 1 {
+            Self::center(window, screen);
+        }
+
+        window.inner_mut().main.style = self.style;
+        self.begin_root_container(window);
+        window.inner_mut().main.draw_rect(screen, MODAL_DIM);
+        window.begin_window(opt);
+        let mut result = f(&mut window.inner_mut().main);
+        self.end_window(window);
+        if measuring {
+            window.inner_mut().main.command_list.clear();
+            Self::center(window, screen);
+        }
+
+        if !window.is_open() || (is_top && self.input.borrow().is_key_pressed(Key::Escape)) {
+            result = result.or(Some(DialogResult::Cancel));
+        }
+        if result.is_some() {
+            window.close();
+            self.modals.retain(|(w, _)| w != window);
+        }
+        result
+    }
+
+    fn center(window: &mut WindowHandle<PR>, screen: Recti) {
+        let mut inner = window.inner_mut();
+        let r = inner.main.rect;
+        inner.main.rect = rect((screen.width - r.width) / 2, (screen.height - r.height) / 2, r.width, r.height);
+    }
+
+    // a modal asking to confirm the message with OK or Cancel
+    pub fn confirm_dialog(&mut self, window: &mut WindowHandle<PR>, message: &str) -> Option<DialogResult> {
+        let font = self.style.font;
+        let atlas = self.canvas.get_atlas();
+        let text_width = message.lines().map(|line| atlas.get_text_size(font, line).width).max().unwrap_or(0);
+        let width = max(text_width + self.style.padding * 2, 200);
+        let opt = WidgetOption::AUTO_SIZE | WidgetOption::NO_RESIZE | WidgetOption::NO_SCROLL;
+        self.modal(window, opt, |c| {
+            c.set_row_widths_height(&[width], 0);
+            for line in message.lines() {
+                c.label(line);
+            }
+            let button_width = (width - c.style.spacing) / 2;
+            c.set_row_widths_height(&[button_width, button_width], 0);
+            let ok = c.button_ex("OK", None, WidgetOption::ALIGN_CENTER).is_submitted();
+            let cancel = c.button_ex("Cancel", None, WidgetOption::ALIGN_CENTER).is_submitted();
+            if ok {
+                Some(DialogResult::Ok)
+            } else if cancel {
+                Some(DialogResult::Cancel)
+            } else {
+                None
+            }
+        })
+    }
+
     pub fn set_style(&mut self, style: &Style) {
         self.style = style.clone()
     }
+
+    // milliseconds elapsed between the last two frames, as given by the host clock
+    pub fn delta_time(&self) -> u64 {
+        self.input.borrow().delta_time()
+    }
+
+    // how long the mouse rests on a widget before its tooltip shows
+    pub fn set_tooltip_delay(&mut self, ms: u64) {
+        self.shared.tooltip.borrow_mut().delay_ms = ms;
+    }
+
+    // binds the key chord to the action, replacing the chord the action had. When another action already uses the
+    // chord nothing changes and the conflict names the other action.
+    pub fn register_shortcut(&mut self, action: &str, shortcut: Shortcut) -> Result<(), ShortcutConflict> {
+        self.shared.shortcuts.borrow_mut().bind(action, shortcut)
+    }
+
+    pub fn unregister_shortcut(&mut self, action: &str) {
+        self.shared.shortcuts.borrow_mut().unbind(action)
+    }
+
+    pub fn shortcut(&self, action: &str) -> Option<Shortcut> {
+        self.shared.shortcuts.borrow().shortcut(action)
+    }
+
+    // an open modal keeps the chords to itself, they don't fire outside of it
+    pub fn is_shortcut_fired(&self, action: &str) -> bool {
+        self.top_modal().is_none() && self.shared.shortcuts.borrow().is_fired(action)
+    }
+
+    // actions whose chord was pressed this frame
+    pub fn fired_shortcuts(&self) -> Vec<String> {
+        match self.top_modal() {
+            Some(_) => Vec::new(),
+            None => self.shared.shortcuts.borrow().fired().to_vec(),
+        }
+    }
+
+    fn top_modal(&self) -> Option<&WindowHandle<PR>> {
+        self.modals.iter().rev().map(|(w, _)| w).find(|w| w.is_open())
+    }
+
+    // cursor requested by the widgets during the last frame, top most window first
+    pub fn cursor(&self) -> CursorIcon {
+        self.root_list.iter().rev().find_map(|w| w.inner().main.cursor()).unwrap_or_default()
+    }
+
+    // caret of the focused text field in the top most window, hosts use it to place the IME candidate window
+    pub fn caret_rect(&self) -> Option<Recti> {
+        self.root_list.iter().rev().find_map(|w| w.inner().main.caret_rect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_state_from_children() {
+        assert_eq!(CheckState::from_children([true, true]), CheckState::Checked);
+        assert_eq!(CheckState::from_children([false, false]), CheckState::Unchecked);
+        assert_eq!(CheckState::from_children([true, false]), CheckState::Mixed);
+        assert_eq!(CheckState::from_children([]), CheckState::Unchecked);
+    }
 }
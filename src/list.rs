@@ -0,0 +1,298 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+use super::*;
+use std::ops::Range;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionMode {
+    Single,
+    // ctrl toggles rows, shift extends the selection from the last clicked row
+    Multi,
+}
+
+// the selected rows of a list box and its keyboard cursor. The rows are kept as sorted runs so selecting every row
+// of a long list costs one run.
+#[derive(Clone, Debug)]
+pub struct ListSelection {
+    pub mode: SelectionMode,
+    ranges: Vec<Range<usize>>,
+    cursor: Option<usize>,
+    anchor: Option<usize>,
+}
+
+impl ListSelection {
+    pub fn new(mode: SelectionMode) -> Self {
+        Self {
+            mode,
+            ranges: Vec::new(),
+            cursor: None,
+            anchor: None,
+        }
+    }
+
+    pub fn is_selected(&self, index: usize) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= index);
+        self.ranges.get(i).is_some_and(|r| r.start <= index)
+    }
+
+    // the selected rows in increasing order
+    pub fn selected(&self) -> impl Iterator<Item = usize> + '_ {
+        self.ranges.iter().flat_map(|r| r.clone())
+    }
+
+    pub fn first(&self) -> Option<usize> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|r| r.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+        self.cursor = None;
+        self.anchor = None;
+    }
+
+    // selects only the row and moves the cursor there
+    pub fn select(&mut self, index: usize) -> bool {
+        self.cursor = Some(index);
+        self.anchor = Some(index);
+        self.replace(index..index + 1)
+    }
+
+    fn replace(&mut self, range: Range<usize>) -> bool {
+        let changed = match self.ranges.as_slice() {
+            [] => !range.is_empty(),
+            [r] => *r != range,
+            _ => true,
+        };
+        self.ranges.clear();
+        if !range.is_empty() {
+            self.ranges.push(range);
+        }
+        changed
+    }
+
+    fn extend_to(&mut self, index: usize) -> bool {
+        let anchor = self.anchor.unwrap_or(index);
+        self.cursor = Some(index);
+        self.replace(min(anchor, index)..max(anchor, index) + 1)
+    }
+
+    fn toggle(&mut self, index: usize) -> bool {
+        self.cursor = Some(index);
+        self.anchor = Some(index);
+        let i = self.ranges.partition_point(|r| r.end < index);
+        match self.ranges.get(i).cloned() {
+            // splits the run around the row
+            Some(r) if r.contains(&index) => {
+                self.ranges
+                    .splice(i..i + 1, [r.start..index, index + 1..r.end].into_iter().filter(|r| !r.is_empty()));
+            }
+            // joins the row to the run ending right before it, and to the one starting right after it
+            Some(r) if r.end == index => {
+                let next = self.ranges.get(i + 1).filter(|n| n.start == index + 1).map(|n| n.end);
+                match next {
+                    Some(end) => {
+                        self.ranges[i].end = end;
+                        self.ranges.remove(i + 1);
+                    }
+                    None => self.ranges[i].end = index + 1,
+                }
+            }
+            Some(r) if r.start == index + 1 => self.ranges[i].start = index,
+            _ => self.ranges.insert(i, index..index + 1),
+        }
+        true
+    }
+
+    pub(crate) fn click(&mut self, index: usize, mods: KeyMode) -> bool {
+        match self.mode {
+            SelectionMode::Multi if mods.is_ctrl() => self.toggle(index),
+            SelectionMode::Multi if mods.is_shift() => self.extend_to(index),
+            _ => self.select(index),
+        }
+    }
+
+    // shift extends the selection to the new row, ctrl only moves the cursor
+    pub(crate) fn move_cursor(&mut self, index: usize, mods: KeyMode) -> bool {
+        match self.mode {
+            SelectionMode::Multi if mods.is_shift() => self.extend_to(index),
+            SelectionMode::Multi if mods.is_ctrl() => {
+                self.cursor = Some(index);
+                false
+            }
+            _ => self.select(index),
+        }
+    }
+
+    pub(crate) fn toggle_cursor(&mut self) -> bool {
+        match (self.mode, self.cursor) {
+            (SelectionMode::Multi, Some(cursor)) => self.toggle(cursor),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn select_all(&mut self, count: usize) -> bool {
+        match self.mode {
+            SelectionMode::Multi => self.replace(0..count),
+            SelectionMode::Single => false,
+        }
+    }
+
+    // forgets the rows past the end of a list that shrank
+    pub(crate) fn truncate(&mut self, count: usize) {
+        self.ranges.retain(|r| r.start < count);
+        if let Some(last) = self.ranges.last_mut() {
+            last.end = min(last.end, count);
+        }
+        self.cursor = self.cursor.filter(|&c| c < count);
+        self.anchor = self.anchor.filter(|&a| a < count);
+    }
+}
+
+impl Default for ListSelection {
+    fn default() -> Self {
+        Self::new(SelectionMode::Single)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(selection: &ListSelection) -> Vec<usize> {
+        selection.selected().collect()
+    }
+
+    #[test]
+    fn click_toggle_and_extend() {
+        let mut selection = ListSelection::new(SelectionMode::Multi);
+        assert!(selection.click(3, KeyMode::NONE));
+        assert!(!selection.click(3, KeyMode::NONE));
+        assert!(selection.click(6, KeyMode::SHIFT));
+        assert_eq!(rows(&selection), vec![3, 4, 5, 6]);
+        // ctrl splits the run and joins it back
+        assert!(selection.click(5, KeyMode::CTRL));
+        assert_eq!(rows(&selection), vec![3, 4, 6]);
+        assert!(selection.click(5, KeyMode::CTRL));
+        assert_eq!(rows(&selection), vec![3, 4, 5, 6]);
+        assert!(selection.click(2, KeyMode::CTRL));
+        assert!(selection.click(7, KeyMode::CTRL));
+        assert!(selection.click(9, KeyMode::CTRL));
+        assert_eq!(rows(&selection), vec![2, 3, 4, 5, 6, 7, 9]);
+        assert_eq!((selection.first(), selection.len()), (Some(2), 7));
+        assert!(selection.is_selected(7) && !selection.is_selected(8) && selection.is_selected(9));
+        // shift extends from the last clicked row
+        assert!(selection.click(8, KeyMode::SHIFT));
+        assert_eq!(rows(&selection), vec![8, 9]);
+        assert_eq!(selection.cursor(), Some(8));
+    }
+
+    #[test]
+    fn single_mode_ignores_the_modifiers() {
+        let mut selection = ListSelection::new(SelectionMode::Single);
+        selection.click(2, KeyMode::NONE);
+        selection.click(4, KeyMode::CTRL);
+        selection.click(6, KeyMode::SHIFT);
+        assert_eq!(rows(&selection), vec![6]);
+        assert!(!selection.select_all(10));
+        assert!(!selection.toggle_cursor());
+    }
+
+    #[test]
+    fn select_all_and_truncate() {
+        let mut selection = ListSelection::new(SelectionMode::Multi);
+        assert!(selection.select_all(100_000));
+        assert!(!selection.select_all(100_000));
+        assert_eq!(selection.len(), 100_000);
+        selection.click(50, KeyMode::CTRL);
+        assert_eq!(selection.len(), 99_999);
+        selection.truncate(40);
+        assert_eq!(selection.len(), 40);
+        selection.truncate(0);
+        assert!(selection.is_empty());
+        assert!(!selection.select_all(0));
+    }
+
+    #[test]
+    fn cursor_moves() {
+        let mut selection = ListSelection::new(SelectionMode::Multi);
+        selection.move_cursor(1, KeyMode::NONE);
+        assert!(selection.move_cursor(3, KeyMode::SHIFT));
+        assert_eq!(rows(&selection), vec![1, 2, 3]);
+        assert!(!selection.move_cursor(5, KeyMode::CTRL));
+        assert!(selection.toggle_cursor());
+        assert_eq!(rows(&selection), vec![1, 2, 3, 5]);
+        assert_eq!(selection.cursor(), Some(5));
+    }
+
+    #[cfg(feature = "builder")]
+    #[test]
+    fn tab_gives_the_list_the_keyboard() {
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 10, 300, 300));
+        let mut panel = ctx.new_panel("list");
+        let mut selection = ListSelection::new(SelectionMode::Multi);
+        let mut frame = |ctx: &mut testing::TestContext, keys: &[Key]| {
+            for key in keys {
+                ctx.input.borrow_mut().keydown(*key);
+            }
+            let mut res = ResourceState::NONE;
+            ctx.frame(|ctx| {
+                ctx.window(&mut window, WidgetOption::NONE, |c| {
+                    c.set_row_widths_height(&[-1], -1);
+                    res = c.list_box(&mut panel, &mut selection, 1000, |i| format!("row {}", i));
+                });
+            });
+            for key in keys.iter().rev() {
+                ctx.input.borrow_mut().keyup(*key);
+            }
+            res
+        };
+        frame(&mut ctx, &[]);
+        frame(&mut ctx, &[Key::Tab]);
+        assert!(frame(&mut ctx, &[Key::Down]).is_changed());
+        assert!(frame(&mut ctx, &[Key::Shift, Key::Down]).is_changed());
+        assert!(frame(&mut ctx, &[Key::Ctrl, Key::A]).is_changed());
+        assert!(frame(&mut ctx, &[Key::Return]).is_submitted());
+        assert_eq!(selection.len(), 1000);
+        assert_eq!(selection.cursor(), Some(1));
+    }
+}
@@ -0,0 +1,267 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+use super::*;
+
+// a dropdown list taller than this many rows scrolls
+pub(crate) const DROPDOWN_ROWS: usize = 8;
+
+// the widget or container body a menu belongs to, keyed by its container since ids are only unique within one
+pub(crate) type MenuOwner = (usize, Option<Id>);
+
+// the open context menu or dropdown list: level 0 is the menu itself, each open submenu adds a level
+pub(crate) struct ContextMenu<PR> {
+    pub(crate) windows: Vec<WindowHandle<PR>>,
+    pub(crate) screen: Dimensioni,
+    owner: Option<MenuOwner>,
+    // the menu opens beside this rectangle
+    anchor: Recti,
+    // a click seen this frame, a widget wins over the body around it
+    request: Option<(MenuOwner, Recti)>,
+    // the submenu item opened at each level
+    path: Vec<Id>,
+    // times each level was drawn since it opened, the first draw only measures the content
+    drawn: Vec<usize>,
+    // the level being drawn
+    pub(crate) depth: usize,
+    claimed: bool,
+    visible: Vec<WindowHandle<PR>>,
+}
+
+impl<PR> ContextMenu<PR> {
+    pub(crate) fn new(screen: Dimensioni) -> Self {
+        Self {
+            windows: Vec::new(),
+            screen,
+            owner: None,
+            anchor: Recti::default(),
+            request: None,
+            path: Vec::new(),
+            drawn: Vec::new(),
+            depth: 0,
+            claimed: false,
+            visible: Vec::new(),
+        }
+    }
+
+    pub(crate) fn request(&mut self, owner: MenuOwner, anchor: Recti) {
+        match self.request {
+            Some(((_, Some(_)), _)) if owner.1.is_none() => (),
+            _ => self.request = Some((owner, anchor)),
+        }
+    }
+
+    pub(crate) fn is_open_for(&self, owner: MenuOwner) -> bool {
+        self.owner == Some(owner)
+    }
+
+    pub(crate) fn owner(&self) -> Option<MenuOwner> {
+        self.owner
+    }
+
+    pub(crate) fn anchor(&self) -> Recti {
+        self.anchor
+    }
+
+    pub(crate) fn is_first_draw(&self, level: usize) -> bool {
+        self.drawn.get(level).map_or(true, |&drawn| drawn <= 1)
+    }
+
+    pub(crate) fn is_submenu_open(&self, level: usize, id: Id) -> bool {
+        self.path.get(level) == Some(&id)
+    }
+
+    // hovering an item of a level closes the submenus below it, hovering a submenu item opens its submenu
+    pub(crate) fn hover_item(&mut self, level: usize, submenu: Option<Id>) {
+        if submenu.is_some_and(|id| self.is_submenu_open(level, id)) {
+            return;
+        }
+        self.path.truncate(level);
+        self.drawn.truncate(level + 1);
+        self.path.extend(submenu);
+    }
+
+    pub(crate) fn close(&mut self) {
+        self.owner = None;
+        self.path.clear();
+        self.drawn.clear();
+    }
+}
+
+impl<PR: Clone> ContextMenu<PR> {
+    // counts a draw of the given level, it shows from its second draw on
+    pub(crate) fn draw_level(&mut self, level: usize, window: &mut WindowHandle<PR>) {
+        self.claimed |= level == 0;
+        if self.drawn.len() <= level {
+            self.drawn.resize(level + 1, 0);
+        }
+        self.drawn[level] += 1;
+        if self.drawn[level] == 1 {
+            // a menu that just opened measures its own rows instead of keeping the width and scroll of the previous one
+            let container = &mut window.inner_mut().main;
+            container.content_size = Vec2i::default();
+            container.scroll = Vec2i::default();
+        }
+        if self.drawn[level] > 1 {
+            self.visible.push(window.clone());
+        }
+    }
+
+    // the rectangles of the menus shown this frame, bottom most first
+    fn visible_rects(&self) -> impl Iterator<Item = Recti> + '_ {
+        self.visible.iter().map(|w| w.inner().main.rect)
+    }
+
+    pub(crate) fn visible_windows(&self) -> Vec<WindowHandle<PR>> {
+        self.visible.clone()
+    }
+
+    // closes the menu when it was not drawn, on escape or on a click outside of it, then opens the requested one
+    pub(crate) fn end_frame(&mut self, input: &Input) {
+        let mouse_pos = input.mouse_pos;
+        let outside = !input.mouse_pressed.is_none() && !self.visible_rects().any(|r| r.contains(&mouse_pos));
+        if !self.claimed || outside || input.is_key_pressed(Key::Escape) {
+            self.close();
+        }
+        if let Some((owner, anchor)) = self.request.take() {
+            self.close();
+            self.owner = Some(owner);
+            self.anchor = anchor;
+        }
+        self.claimed = false;
+        self.visible.clear();
+    }
+
+    pub(crate) fn is_over(&self, pos: Vec2i) -> Option<WindowHandle<PR>> {
+        self.visible.iter().rev().find(|w| w.inner().main.rect.contains(&pos)).cloned()
+    }
+}
+
+// places a menu of the given size on the right of the anchor, flipping it to the left and upward near the screen edges
+pub(crate) fn menu_rect(anchor: Recti, size: Dimensioni, screen: Dimensioni) -> Recti {
+    let mut x = anchor.x + anchor.width;
+    let mut y = anchor.y;
+    if x + size.width > screen.width {
+        x = anchor.x - size.width;
+    }
+    if y + size.height > screen.height {
+        y = anchor.y + anchor.height - size.height;
+    }
+    rect(
+        x.min(screen.width - size.width).max(0),
+        y.min(screen.height - size.height).max(0),
+        size.width,
+        size.height,
+    )
+}
+
+// places a menu of the given size below the anchor, or above it near the bottom of the screen
+pub(crate) fn pulldown_rect(anchor: Recti, size: Dimensioni, screen: Dimensioni) -> Recti {
+    let mut y = anchor.y + anchor.height;
+    if y + size.height > screen.height {
+        y = anchor.y - size.height;
+    }
+    rect(
+        anchor.x.min(screen.width - size.width).max(0),
+        y.min(screen.height - size.height).max(0),
+        size.width,
+        size.height,
+    )
+}
+
+// places a list as wide as the anchor below it, or above it when there is more room there
+pub(crate) fn dropdown_rect(anchor: Recti, height: i32, screen: Dimensioni) -> Recti {
+    let below = screen.height - (anchor.y + anchor.height);
+    let above = anchor.y;
+    if height > below && above > below {
+        let height = min(height, above);
+        rect(anchor.x, anchor.y - height, anchor.width, height)
+    } else {
+        rect(anchor.x, anchor.y + anchor.height, anchor.width, min(height, max(below, 0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xywh(r: Recti) -> (i32, i32, i32, i32) {
+        (r.x, r.y, r.width, r.height)
+    }
+
+    #[test]
+    fn pulldown_placement() {
+        let screen = Dimensioni::new(800, 600);
+        let size = Dimensioni::new(150, 200);
+        // below the anchor
+        assert_eq!(xywh(pulldown_rect(rect(10, 0, 60, 20), size, screen)), (10, 20, 150, 200));
+        // above it near the bottom, pushed left near the right edge
+        assert_eq!(xywh(pulldown_rect(rect(700, 500, 60, 20), size, screen)), (650, 300, 150, 200));
+        // clamped to the screen when it fits neither below nor above
+        assert_eq!(xywh(pulldown_rect(rect(10, 100, 60, 20), Dimensioni::new(150, 550), screen)), (10, 0, 150, 550));
+        // taller than the screen sticks to the top
+        assert_eq!(xywh(pulldown_rect(rect(10, 100, 60, 20), Dimensioni::new(900, 700), screen)), (0, 0, 900, 700));
+    }
+
+    #[test]
+    fn dropdown_placement() {
+        let screen = Dimensioni::new(800, 600);
+        // below the combo box, as wide as it
+        assert_eq!(xywh(dropdown_rect(rect(10, 100, 120, 20), 200, screen)), (10, 120, 120, 200));
+        // above it when there is more room there, cut to the room
+        assert_eq!(xywh(dropdown_rect(rect(10, 500, 120, 20), 200, screen)), (10, 300, 120, 200));
+        assert_eq!(xywh(dropdown_rect(rect(10, 400, 120, 20), 900, screen)), (10, 0, 120, 400));
+        // below and cut when there is more room below
+        assert_eq!(xywh(dropdown_rect(rect(10, 200, 120, 20), 900, screen)), (10, 220, 120, 380));
+        // an anchor off the bottom of the screen gets no room below
+        assert_eq!(xywh(dropdown_rect(rect(10, 590, 120, 20), 100, screen)), (10, 490, 120, 100));
+    }
+
+    #[cfg(feature = "builder")]
+    #[test]
+    fn main_menu_bar_stays_below_an_open_modal() {
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 40, 200, 200));
+        let mut modal = ctx.new_modal("modal", rect(0, 0, 200, 100));
+        ctx.open_modal(&mut modal);
+        for step in 0..4 {
+            // a click on the window raises it above the bar
+            if step == 1 {
+                ctx.bring_to_front(&mut window);
+            }
+            ctx.frame(|ctx| {
+                ctx.main_menu_bar(|bar| bar.menu("File", |_| {}));
+                let bar = ctx.main_menu.as_ref().unwrap().zindex();
+                assert!(bar < modal.zindex(), "frame {}: bar {} over modal {}", step, bar, modal.zindex());
+                ctx.window(&mut window, WidgetOption::NONE, |_| {});
+                ctx.modal(&mut modal, WidgetOption::NONE, |_| None);
+            });
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+
+// a value the slider and number widgets edit. They work on it as an f64 and store the result back with the rounding
+// of the type: integers snap to whole numbers and saturate at their bounds.
+pub trait Numeric: Copy + PartialOrd {
+    fn to_f64(self) -> f64;
+    fn from_f64(v: f64) -> Self;
+    // the value moved by `delta`, integers stay exact far beyond the 53 bits of an f64
+    fn offset(self, delta: f64) -> Self;
+    // the value as text, floats with `precision` digits after the point
+    fn format(self, precision: usize) -> String;
+    fn parse(text: &str) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty)*) => {
+        $(
+            impl Numeric for $t {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(v: f64) -> Self {
+                    v.round() as $t
+                }
+
+                fn offset(self, delta: f64) -> Self {
+                    (self as i128 + delta.round() as i128).clamp(<$t>::MIN as i128, <$t>::MAX as i128) as $t
+                }
+
+                fn format(self, _precision: usize) -> String {
+                    self.to_string()
+                }
+
+                // a fraction typed in is rounded
+                fn parse(text: &str) -> Option<Self> {
+                    let text = text.trim();
+                    text.parse().ok().or_else(|| text.parse::<f64>().ok().map(Self::from_f64))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_float {
+    ($($t:ty)*) => {
+        $(
+            impl Numeric for $t {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(v: f64) -> Self {
+                    v as $t
+                }
+
+                fn offset(self, delta: f64) -> Self {
+                    (self as f64 + delta) as $t
+                }
+
+                fn format(self, precision: usize) -> String {
+                    format!("{:.*}", precision, self)
+                }
+
+                fn parse(text: &str) -> Option<Self> {
+                    text.trim().parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize);
+impl_float!(f32 f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(i32::parse(" 42 "), Some(42));
+        assert_eq!(i32::parse("2.6"), Some(3));
+        assert_eq!(u8::parse("-1"), Some(0));
+        assert_eq!(u8::parse("x"), None);
+        assert_eq!(f32::parse("0.5"), Some(0.5));
+        assert_eq!(f64::parse(""), None);
+    }
+
+    #[test]
+    fn offset() {
+        assert_eq!(5i32.offset(1.4), 6);
+        assert_eq!(5i32.offset(-0.4), 5);
+        assert_eq!(250u8.offset(10.), 255);
+        assert_eq!(3u8.offset(-10.), 0);
+        assert_eq!((i64::MAX - 1).offset(1.), i64::MAX);
+        assert_eq!(u64::MAX.offset(-1.), u64::MAX - 1);
+        assert_eq!(1.5f32.offset(0.25), 1.75);
+    }
+}
@@ -0,0 +1,503 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+use super::*;
+use std::fmt::Write as _;
+use std::path::Path;
+
+// one call made on `Input` by the host
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputEvent {
+    Time(u64),
+    MouseMove(i32, i32),
+    MouseDown(i32, i32, MouseButton),
+    MouseUp(i32, i32, MouseButton),
+    Scroll(i32, i32),
+    KeyDown(Key),
+    KeyUp(Key),
+    Text(String),
+    Composition(String, usize, usize),
+    GamepadDown(GamepadButton),
+    GamepadUp(GamepadButton),
+    TouchDown(u64, i32, i32),
+    TouchMove(u64, i32, i32),
+    TouchUp(u64, i32, i32),
+}
+
+impl InputEvent {
+    pub fn apply(&self, input: &mut Input) {
+        match self {
+            InputEvent::Time(ms) => input.set_time(*ms),
+            InputEvent::MouseMove(x, y) => input.mousemove(*x, *y),
+            InputEvent::MouseDown(x, y, btn) => input.mousedown(*x, *y, *btn),
+            InputEvent::MouseUp(x, y, btn) => input.mouseup(*x, *y, *btn),
+            InputEvent::Scroll(x, y) => input.scroll(*x, *y),
+            InputEvent::KeyDown(key) => input.keydown(*key),
+            InputEvent::KeyUp(key) => input.keyup(*key),
+            InputEvent::Text(text) => input.text(text.as_str()),
+            InputEvent::Composition(text, start, length) => input.composition(text.as_str(), *start, *length),
+            InputEvent::GamepadDown(btn) => input.gamepaddown(*btn),
+            InputEvent::GamepadUp(btn) => input.gamepadup(*btn),
+            InputEvent::TouchDown(id, x, y) => input.touchdown(*id, *x, *y),
+            InputEvent::TouchMove(id, x, y) => input.touchmove(*id, *x, *y),
+            InputEvent::TouchUp(id, x, y) => input.touchup(*id, *x, *y),
+        }
+    }
+
+    fn write(&self, out: &mut String) {
+        let _ = match self {
+            InputEvent::Time(ms) => writeln!(out, "time {}", ms),
+            InputEvent::MouseMove(x, y) => writeln!(out, "move {} {}", x, y),
+            InputEvent::MouseDown(x, y, btn) => writeln!(out, "down {} {} {}", x, y, btn.bits()),
+            InputEvent::MouseUp(x, y, btn) => writeln!(out, "up {} {} {}", x, y, btn.bits()),
+            InputEvent::Scroll(x, y) => writeln!(out, "scroll {} {}", x, y),
+            InputEvent::KeyDown(key) => writeln!(out, "keydown {}", key.name()),
+            InputEvent::KeyUp(key) => writeln!(out, "keyup {}", key.name()),
+            InputEvent::Text(text) => writeln!(out, "text {}", to_hex(text)),
+            InputEvent::Composition(text, start, length) => writeln!(out, "compose {} {} {}", start, length, to_hex(text)),
+            InputEvent::GamepadDown(btn) => writeln!(out, "gamepaddown {}", btn.bits()),
+            InputEvent::GamepadUp(btn) => writeln!(out, "gamepadup {}", btn.bits()),
+            InputEvent::TouchDown(id, x, y) => writeln!(out, "touchdown {} {} {}", id, x, y),
+            InputEvent::TouchMove(id, x, y) => writeln!(out, "touchmove {} {} {}", id, x, y),
+            InputEvent::TouchUp(id, x, y) => writeln!(out, "touchup {} {} {}", id, x, y),
+        };
+    }
+
+    fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let args: Vec<&str> = words.collect();
+        let arg = |i: usize| args.get(i).copied().ok_or_else(|| format!("missing argument in '{}'", line));
+        let num = |i: usize| arg(i)?.parse::<i64>().map_err(|e| format!("{} in '{}'", e, line));
+        let key = |i: usize| Key::from_name(arg(i)?).ok_or_else(|| format!("unknown key in '{}'", line));
+        // hex strings are optional, an empty text has no argument
+        let text = |i: usize| from_hex(args.get(i).copied().unwrap_or_default()).ok_or_else(|| format!("bad text in '{}'", line));
+        let event = match name {
+            "time" => InputEvent::Time(num(0)? as u64),
+            "move" => InputEvent::MouseMove(num(0)? as i32, num(1)? as i32),
+            "down" => InputEvent::MouseDown(num(0)? as i32, num(1)? as i32, MouseButton::from_bits_truncate(num(2)? as u32)),
+            "up" => InputEvent::MouseUp(num(0)? as i32, num(1)? as i32, MouseButton::from_bits_truncate(num(2)? as u32)),
+            "scroll" => InputEvent::Scroll(num(0)? as i32, num(1)? as i32),
+            "keydown" => InputEvent::KeyDown(key(0)?),
+            "keyup" => InputEvent::KeyUp(key(0)?),
+            "text" => InputEvent::Text(text(0)?),
+            "compose" => InputEvent::Composition(text(2)?, num(0)? as usize, num(1)? as usize),
+            "gamepaddown" => InputEvent::GamepadDown(GamepadButton::from_bits_truncate(num(0)? as u32)),
+            "gamepadup" => InputEvent::GamepadUp(GamepadButton::from_bits_truncate(num(0)? as u32)),
+            "touchdown" => InputEvent::TouchDown(num(0)? as u64, num(1)? as i32, num(2)? as i32),
+            "touchmove" => InputEvent::TouchMove(num(0)? as u64, num(1)? as i32, num(2)? as i32),
+            "touchup" => InputEvent::TouchUp(num(0)? as u64, num(1)? as i32, num(2)? as i32),
+            _ => return Err(format!("unknown event '{}'", line)),
+        };
+        Ok(event)
+    }
+}
+
+fn to_hex(text: &str) -> String {
+    text.bytes().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<String> {
+    let bytes: Option<Vec<u8>> = (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+        .collect();
+    String::from_utf8(bytes?).ok()
+}
+
+const RECORDING_HEADER: &str = "microui-redux input recording 2";
+
+// the input events of consecutive frames, saved as text with one event per line and a "frame" line before each frame.
+// `start` holds the buttons and keys already down and the text being composed when the recording began, they are under
+// a "start" line.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Recording {
+    pub start: Vec<InputEvent>,
+    pub frames: Vec<Vec<InputEvent>>,
+}
+
+impl Recording {
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        out.push_str(RECORDING_HEADER);
+        out.push_str("\nstart\n");
+        for event in &self.start {
+            event.write(&mut out);
+        }
+        for frame in &self.frames {
+            out.push_str("frame\n");
+            for event in frame {
+                event.write(&mut out);
+            }
+        }
+        out
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        if lines.next() != Some(RECORDING_HEADER) {
+            return Err("not an input recording".to_string());
+        }
+        let mut lines = lines.map(str::trim).filter(|l| !l.is_empty());
+        if lines.next() != Some("start") {
+            return Err("missing start state".to_string());
+        }
+        let mut start = Vec::new();
+        let mut frames: Vec<Vec<InputEvent>> = Vec::new();
+        for line in lines {
+            if line == "frame" {
+                frames.push(Vec::new());
+                continue;
+            }
+            let event = InputEvent::parse(line)?;
+            match frames.last_mut() {
+                Some(frame) => frame.push(event),
+                None => start.push(event),
+            }
+        }
+        Ok(Self { start, frames })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_text())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Self::from_text(text.as_str()).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+// feeds a recording back into the input, one recorded frame before each `Context::frame`
+#[derive(Clone, Debug)]
+pub struct Player {
+    recording: Recording,
+    frame: usize,
+}
+
+impl Player {
+    pub fn new(recording: Recording) -> Self {
+        Self { recording, frame: 0 }
+    }
+
+    // applies the events of the next frame, returns false once every frame has been played. The first frame starts
+    // from the state the input had when it was recorded, whatever the host holds down at that time.
+    pub fn feed(&mut self, input: &mut Input) -> bool {
+        if self.frame == 0 {
+            input.restore(&self.recording.start);
+        }
+        match self.recording.frames.get(self.frame) {
+            Some(events) => {
+                for event in events {
+                    event.apply(input);
+                }
+                self.frame += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.recording.frames.len()
+    }
+
+    pub fn rewind(&mut self) {
+        self.frame = 0;
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Recorder {
+    recording: Recording,
+    current: Vec<InputEvent>,
+    paused: bool,
+    // the recording was asked for while fingers were down, it starts once they are all lifted
+    waiting: bool,
+}
+
+impl Input {
+    // starts capturing every call made on the input, frames are split when the context begins a frame. The touches
+    // in progress can't be replayed, with fingers down the recording only starts after the first frame that ends with
+    // all of them lifted.
+    pub fn start_recording(&mut self) {
+        let waiting = !self.touch.is_idle();
+        let recording = Recording {
+            start: if waiting { Vec::new() } else { self.snapshot() },
+            frames: Vec::new(),
+        };
+        self.recorder = Some(Recorder {
+            recording,
+            waiting,
+            ..Recorder::default()
+        });
+    }
+
+    // the frames recorded so far, events given after the last frame began are kept as a frame of their own. None when
+    // the recording is still waiting for the fingers to be lifted.
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recorder.take().filter(|recorder| !recorder.waiting).map(|mut recorder| {
+            if !recorder.current.is_empty() {
+                recorder.recording.frames.push(recorder.current);
+            }
+            recorder.recording
+        })
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    // the time, the mouse position, what is held down and the text being composed, as the events leading to them
+    fn snapshot(&self) -> Vec<InputEvent> {
+        let (x, y) = (self.mouse_pos.x, self.mouse_pos.y);
+        let mut events = vec![InputEvent::Time(self.time), InputEvent::MouseMove(x, y)];
+        if !self.mouse_down.is_empty() {
+            events.push(InputEvent::MouseDown(x, y, self.mouse_down));
+        }
+        if !self.gamepad_down.is_empty() {
+            events.push(InputEvent::GamepadDown(self.gamepad_down));
+        }
+        events.extend(
+            Key::ALL
+                .iter()
+                .filter(|key| self.keys_down.contains(**key))
+                .map(|key| InputEvent::KeyDown(*key)),
+        );
+        if self.is_composing() {
+            let (start, length) = self.composition_cursor;
+            events.push(InputEvent::Composition(self.composition_text.clone(), start, length));
+        }
+        events
+    }
+
+    // forgets the current state and takes the one of a snapshot, what it holds down counts as held rather than pressed
+    fn restore(&mut self, snapshot: &[InputEvent]) {
+        let recorder = self.recorder.take();
+        *self = Input {
+            thresholds: self.thresholds,
+            ..Input::default()
+        };
+        for event in snapshot {
+            event.apply(self);
+        }
+        self.epilogue();
+        self.last_time = Some(self.time);
+        self.recorder = recorder;
+    }
+
+    pub(crate) fn record(&mut self, event: InputEvent) {
+        if let Some(recorder) = self.recorder.as_mut().filter(|r| !r.paused && !r.waiting) {
+            recorder.current.push(event);
+        }
+    }
+
+    pub(crate) fn record_frame(&mut self) {
+        if let Some(recorder) = self.recorder.as_mut().filter(|r| !r.waiting) {
+            let events = std::mem::take(&mut recorder.current);
+            recorder.recording.frames.push(events);
+        }
+    }
+
+    // starts a waiting recording at the end of a frame once no finger is down
+    pub(crate) fn start_waiting_recording(&mut self) {
+        if self.recorder.as_ref().is_some_and(|r| r.waiting) && self.touch.is_idle() {
+            let start = self.snapshot();
+            if let Some(recorder) = self.recorder.as_mut() {
+                recorder.recording.start = start;
+                recorder.waiting = false;
+            }
+        }
+    }
+
+    // runs calls the input makes on itself, like the mouse events derived from touches, without recording them
+    pub(crate) fn synthesize<F: FnOnce(&mut Self)>(&mut self, f: F) {
+        let paused = self.recorder.as_ref().is_some_and(|r| r.paused);
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.paused = true;
+        }
+        f(self);
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.paused = paused;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_round_trip() {
+        let recording = Recording {
+            start: vec![InputEvent::Time(40), InputEvent::MouseMove(3, 4), InputEvent::KeyDown(Key::Shift)],
+            frames: vec![
+                vec![InputEvent::Time(56), InputEvent::MouseMove(-5, 7), InputEvent::Scroll(0, -3)],
+                vec![],
+                vec![
+                    InputEvent::MouseDown(10, 20, MouseButton::LEFT),
+                    InputEvent::MouseUp(10, 20, MouseButton::LEFT),
+                    InputEvent::KeyDown(Key::Num7),
+                    InputEvent::KeyUp(Key::PageDown),
+                    InputEvent::Text("a b\né".to_string()),
+                    InputEvent::Text(String::new()),
+                    InputEvent::Composition("かな".to_string(), 1, 2),
+                    InputEvent::GamepadDown(GamepadButton::RIGHT_SHOULDER),
+                    InputEvent::GamepadUp(GamepadButton::RIGHT_SHOULDER),
+                    InputEvent::TouchDown(9, 1, 2),
+                    InputEvent::TouchMove(9, 3, 4),
+                    InputEvent::TouchUp(9, 3, 4),
+                ],
+            ],
+        };
+        assert_eq!(Recording::from_text(recording.to_text().as_str()), Ok(recording));
+        assert!(Recording::from_text("frame\ntime 1").is_err());
+    }
+
+    #[test]
+    fn every_key_round_trips() {
+        for key in Key::ALL {
+            assert_eq!(Key::from_name(key.name()), Some(*key));
+            let mut text = String::new();
+            InputEvent::KeyDown(*key).write(&mut text);
+            assert_eq!(InputEvent::parse(text.trim()), Ok(InputEvent::KeyDown(*key)));
+        }
+        assert_eq!(Key::from_name("Num10"), None);
+    }
+
+    // what a context does to the input around a frame
+    fn frame(input: &mut Input) {
+        input.prelude();
+        input.epilogue();
+    }
+
+    #[test]
+    fn start_keeps_the_composition() {
+        let mut input = Input::default();
+        input.set_time(100);
+        input.composition("かな", 1, 1);
+        frame(&mut input);
+        input.start_recording();
+        input.keydown(Key::Return);
+        frame(&mut input);
+        let recording = input.stop_recording().unwrap();
+        assert!(recording.start.contains(&InputEvent::Composition("かな".to_string(), 1, 1)));
+
+        let mut replay = Input::default();
+        let mut player = Player::new(Recording::from_text(recording.to_text().as_str()).unwrap());
+        assert!(player.feed(&mut replay));
+        assert_eq!((replay.composition_text(), replay.composition_cursor()), ("かな", (1, 1)));
+        assert!(replay.is_key_pressed(Key::Return));
+    }
+
+    #[test]
+    fn recording_waits_for_the_fingers_to_lift() {
+        let mut input = Input::default();
+        input.set_time(100);
+        input.touchdown(1, 10, 10);
+        frame(&mut input);
+        input.start_recording();
+        input.set_time(116);
+        input.touchmove(1, 40, 10);
+        frame(&mut input);
+        assert!(input.is_recording());
+        input.set_time(132);
+        input.touchup(1, 40, 10);
+        frame(&mut input);
+        // recorded from here on
+        input.set_time(148);
+        input.mousemove(70, 80);
+        frame(&mut input);
+        let recording = input.stop_recording().unwrap();
+        assert_eq!(recording.start, vec![InputEvent::Time(132), InputEvent::MouseMove(40, 10)]);
+        assert_eq!(recording.frames, vec![vec![InputEvent::Time(148), InputEvent::MouseMove(70, 80)]]);
+
+        // stopped before the fingers are lifted, nothing was recorded
+        input.touchdown(2, 10, 10);
+        frame(&mut input);
+        input.start_recording();
+        input.mousemove(1, 1);
+        frame(&mut input);
+        assert_eq!(input.stop_recording(), None);
+    }
+
+    #[cfg(feature = "builder")]
+    #[test]
+    fn replayed_click_focuses_the_button() {
+        // runs one frame with a button in a window, returns whether it was submitted and whether it has the focus
+        fn frame(ctx: &mut Context<(), testing::NullRenderer>, window: &mut WindowHandle<()>) -> (bool, bool) {
+            let mut state = (false, false);
+            ctx.frame(|ctx| {
+                ctx.window(window, WidgetOption::NONE, |c| {
+                    c.set_row_widths_height(&[-1], 0);
+                    let res = c.button_ex("Button", None, WidgetOption::NONE);
+                    state = (res.is_submitted(), c.focus.is_some() && c.focus == c.idmngr.last_id());
+                });
+            });
+            state
+        }
+
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 10, 300, 300));
+        ctx.input.borrow_mut().mousemove(60, 39);
+        frame(&mut ctx, &mut window);
+        frame(&mut ctx, &mut window);
+        ctx.input.borrow_mut().start_recording();
+        let mut live = Vec::new();
+        for step in 0..3 {
+            {
+                let mut input = ctx.input.borrow_mut();
+                input.set_time(100 + step * 16);
+                match step {
+                    1 => input.mousedown(60, 39, MouseButton::LEFT),
+                    2 => input.mouseup(60, 39, MouseButton::LEFT),
+                    _ => {}
+                }
+            }
+            live.push(frame(&mut ctx, &mut window));
+        }
+        let recording = ctx.input.borrow_mut().stop_recording().unwrap();
+        assert_eq!(live, vec![(false, false), (true, true), (false, false)]);
+
+        // the replay starts with the host holding the button down away from the window, the replay doesn't see it
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 10, 300, 300));
+        ctx.input.borrow_mut().mousemove(60, 39);
+        frame(&mut ctx, &mut window);
+        frame(&mut ctx, &mut window);
+        ctx.input.borrow_mut().mousedown(500, 500, MouseButton::LEFT);
+        let mut player = Player::new(Recording::from_text(recording.to_text().as_str()).unwrap());
+        let mut replayed = Vec::new();
+        while player.feed(&mut ctx.input.borrow_mut()) {
+            replayed.push(frame(&mut ctx, &mut window));
+        }
+        assert!(player.is_finished());
+        assert_eq!(replayed, live);
+    }
+}
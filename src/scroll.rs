@@ -0,0 +1,99 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+use super::*;
+
+// an axis along which a container under the mouse can scroll, noted while drawing its scrollbar
+#[derive(Clone, Copy)]
+pub(crate) struct Scrollable {
+    pub(crate) anim_id: Id,
+    // where the container is heading, its pending smooth scroll counts as already done
+    pub(crate) scroll: i32,
+    pub(crate) max: i32,
+}
+
+impl Scrollable {
+    pub(crate) fn can_move(&self, delta: i32) -> bool {
+        if delta < 0 {
+            self.scroll > 0
+        } else {
+            self.scroll < self.max
+        }
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wheel_moves_the_innermost_container_that_can_move() {
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 10, 300, 200));
+        let mut panel = ctx.new_panel("panel");
+        let mut time = 0;
+        // scrolls by `delta` and lets the smooth scroll settle, returns the scroll of the panel and of the window
+        let mut wheel = |ctx: &mut testing::TestContext, delta: i32| {
+            for step in 0..20 {
+                time += 16;
+                ctx.input.borrow_mut().set_time(time);
+                if step == 0 {
+                    ctx.input.borrow_mut().scroll(0, delta);
+                }
+                ctx.frame(|ctx| {
+                    ctx.window(&mut window, WidgetOption::NONE, |c| {
+                        c.set_row_widths_height(&[-1], 100);
+                        c.panel(&mut panel, WidgetOption::NONE, |p| {
+                            let mut p = p.inner_mut();
+                            p.set_row_widths_height(&[-1], 0);
+                            for i in 0..20 {
+                                p.button_ex(format!("inner {}", i).as_str(), None, WidgetOption::NONE);
+                            }
+                        });
+                        c.set_row_widths_height(&[-1], 0);
+                        for i in 0..20 {
+                            c.button_ex(format!("outer {}", i).as_str(), None, WidgetOption::NONE);
+                        }
+                    });
+                });
+            }
+            (panel.inner().scroll.y, window.inner().main.scroll.y)
+        };
+        ctx.input.borrow_mut().mousemove(60, 60);
+        assert_eq!(wheel(&mut ctx, 0), (0, 0));
+        assert_eq!(wheel(&mut ctx, 30), (30, 0));
+        assert_eq!(wheel(&mut ctx, -50), (0, 0));
+        // the panel can't go further up and neither can the window
+        assert_eq!(wheel(&mut ctx, -10), (0, 0));
+        let (inner, outer) = wheel(&mut ctx, 10000);
+        assert!(inner > 30 && outer == 0);
+        // the panel is at its end, the window takes the wheel
+        assert_eq!(wheel(&mut ctx, 20), (inner, 20));
+    }
+}
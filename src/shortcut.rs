@@ -0,0 +1,221 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+use super::*;
+use std::fmt;
+
+// a key chord like Ctrl+S or Ctrl+Shift+P, only the ctrl, shift and alt modifiers count
+#[derive(Copy, Clone, Debug)]
+pub struct Shortcut {
+    pub mods: KeyMode,
+    pub key: Key,
+}
+
+impl Shortcut {
+    pub fn new(mods: KeyMode, key: Key) -> Self {
+        Self {
+            mods: mods & (KeyMode::CTRL | KeyMode::SHIFT | KeyMode::ALT),
+            key,
+        }
+    }
+
+    // chords without ctrl or alt are plain typing for a focused text field
+    pub fn is_plain(&self) -> bool {
+        !self.mods.is_ctrl() && !self.mods.is_alt()
+    }
+
+    fn matches(&self, input: &Input) -> bool {
+        let mods = input.key_mode() & (KeyMode::CTRL | KeyMode::SHIFT | KeyMode::ALT);
+        input.is_key_pressed(self.key) && !input.is_key_repeated(self.key) && mods.bits() == self.mods.bits()
+    }
+}
+
+impl PartialEq for Shortcut {
+    fn eq(&self, other: &Self) -> bool {
+        self.mods.bits() == other.mods.bits() && self.key == other.key
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mods.is_ctrl() {
+            write!(f, "Ctrl+")?;
+        }
+        if self.mods.is_shift() {
+            write!(f, "Shift+")?;
+        }
+        if self.mods.is_alt() {
+            write!(f, "Alt+")?;
+        }
+        let name = self.key.name();
+        write!(f, "{}", name.strip_prefix("Num").unwrap_or(name))
+    }
+}
+
+// the chord is already bound to another action
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShortcutConflict {
+    pub existing: String,
+}
+
+impl fmt::Display for ShortcutConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the shortcut is already bound to '{}'", self.existing)
+    }
+}
+
+impl std::error::Error for ShortcutConflict {}
+
+#[derive(Default)]
+pub(crate) struct Shortcuts {
+    bindings: Vec<(String, Shortcut)>,
+    fired: Vec<String>,
+    // a text field had the keyboard during the last frame
+    pub(crate) text_input: bool,
+}
+
+impl Shortcuts {
+    // binds the chord to the action, replacing the action's previous chord. Fails with the action already using it.
+    pub(crate) fn bind(&mut self, action: &str, shortcut: Shortcut) -> Result<(), ShortcutConflict> {
+        if let Some((other, _)) = self.bindings.iter().find(|(a, s)| *s == shortcut && a != action) {
+            return Err(ShortcutConflict { existing: other.clone() });
+        }
+        self.unbind(action);
+        self.bindings.push((action.to_string(), shortcut));
+        Ok(())
+    }
+
+    pub(crate) fn unbind(&mut self, action: &str) {
+        self.bindings.retain(|(a, _)| a != action);
+    }
+
+    pub(crate) fn shortcut(&self, action: &str) -> Option<Shortcut> {
+        self.bindings.iter().find(|(a, _)| a == action).map(|(_, s)| *s)
+    }
+
+    pub(crate) fn update(&mut self, input: &Input) {
+        self.fired.clear();
+        for (action, shortcut) in &self.bindings {
+            if shortcut.matches(input) && !(self.text_input && shortcut.is_plain()) {
+                self.fired.push(action.clone());
+            }
+        }
+    }
+
+    pub(crate) fn is_fired(&self, action: &str) -> bool {
+        self.fired.iter().any(|a| a == action)
+    }
+
+    pub(crate) fn fired(&self) -> &[String] {
+        &self.fired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(Shortcut::new(KeyMode::CTRL, Key::S).to_string(), "Ctrl+S");
+        assert_eq!(Shortcut::new(KeyMode::CTRL | KeyMode::SHIFT, Key::P).to_string(), "Ctrl+Shift+P");
+        assert_eq!(Shortcut::new(KeyMode::ALT | KeyMode::CTRL, Key::F4).to_string(), "Ctrl+Alt+F4");
+        assert_eq!(Shortcut::new(KeyMode::NONE, Key::Num1).to_string(), "1");
+        assert_eq!(Shortcut::new(KeyMode::SHIFT, Key::PageUp).to_string(), "Shift+PageUp");
+    }
+
+    #[test]
+    fn new_keeps_only_ctrl_shift_and_alt() {
+        let shortcut = Shortcut::new(KeyMode::CTRL | KeyMode::BACKSPACE | KeyMode::RETURN, Key::Z);
+        assert_eq!(shortcut.mods.bits(), KeyMode::CTRL.bits());
+        assert!(shortcut == Shortcut::new(KeyMode::CTRL, Key::Z));
+        assert!(!shortcut.is_plain());
+        assert!(Shortcut::new(KeyMode::SHIFT | KeyMode::RETURN, Key::Z).is_plain());
+    }
+
+    #[test]
+    fn bind_refuses_a_taken_chord() {
+        let mut shortcuts = Shortcuts::default();
+        let save = Shortcut::new(KeyMode::CTRL, Key::S);
+        assert_eq!(shortcuts.bind("save", save), Ok(()));
+        // binding the same chord to the same action again is fine
+        assert_eq!(shortcuts.bind("save", save), Ok(()));
+        assert_eq!(shortcuts.bind("search", save), Err(ShortcutConflict { existing: "save".into() }));
+        assert!(shortcuts.shortcut("search").is_none());
+        // rebinding an action frees its old chord
+        let save_as = Shortcut::new(KeyMode::CTRL | KeyMode::SHIFT, Key::S);
+        assert_eq!(shortcuts.bind("save", save_as), Ok(()));
+        assert_eq!(shortcuts.bind("search", save), Ok(()));
+        assert!(shortcuts.shortcut("save") == Some(save_as));
+        shortcuts.unbind("search");
+        assert!(shortcuts.shortcut("search").is_none());
+    }
+
+    #[cfg(feature = "builder")]
+    #[test]
+    fn open_modal_keeps_the_shortcuts_to_itself() {
+        // runs a frame, pressing ctrl+g when asked. Returns whether the button of the window, the button of the modal
+        // and the context saw the chord.
+        fn frame(ctx: &mut testing::TestContext, window: &mut WindowHandle<()>, modal: &mut WindowHandle<()>, press: bool) -> (bool, bool, bool) {
+            if press {
+                let mut input = ctx.input.borrow_mut();
+                input.keydown(Key::Ctrl);
+                input.keydown(Key::G);
+            }
+            let mut fired = (false, false, false);
+            ctx.frame(|ctx| {
+                ctx.window(window, WidgetOption::NONE, |c| {
+                    c.set_row_widths_height(&[-1], 0);
+                    fired.0 = c.button_shortcut("Go", "go", WidgetOption::NONE).is_submitted();
+                });
+                ctx.modal(modal, WidgetOption::NONE, |c| {
+                    c.set_row_widths_height(&[-1], 0);
+                    fired.1 = c.button_shortcut("Go", "go", WidgetOption::NONE).is_submitted();
+                    None
+                });
+                fired.2 = ctx.is_shortcut_fired("go");
+            });
+            if press {
+                let mut input = ctx.input.borrow_mut();
+                input.keyup(Key::G);
+                input.keyup(Key::Ctrl);
+            }
+            fired
+        }
+
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 10, 300, 300));
+        let mut modal = ctx.new_modal("modal", rect(0, 0, 200, 100));
+        assert_eq!(ctx.register_shortcut("go", Shortcut::new(KeyMode::CTRL, Key::G)), Ok(()));
+        assert_eq!(frame(&mut ctx, &mut window, &mut modal, true), (true, false, true));
+        ctx.open_modal(&mut modal);
+        frame(&mut ctx, &mut window, &mut modal, false);
+        assert_eq!(frame(&mut ctx, &mut window, &mut modal, true), (false, true, false));
+    }
+}
@@ -0,0 +1,274 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+use super::*;
+
+// the narrowest a column can be resized to
+pub const MIN_COLUMN_WIDTH: i32 = 24;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    pub fn is_ascending(&self) -> bool {
+        matches!(self, SortOrder::Ascending)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TableColumn {
+    pub label: String,
+    pub width: i32,
+    pub sortable: bool,
+}
+
+impl TableColumn {
+    pub fn new(label: &str, width: i32) -> Self {
+        Self {
+            label: label.to_string(),
+            width,
+            sortable: false,
+        }
+    }
+
+    pub fn sortable(mut self) -> Self {
+        self.sortable = true;
+        self
+    }
+}
+
+// what a table remembers between frames: its columns with their widths, the order they are shown in, the sorted
+// column and the row selection. Column indices always refer to `columns`, whatever order they are shown in.
+#[derive(Clone, Debug)]
+pub struct TableState {
+    pub columns: Vec<TableColumn>,
+    pub selection: ListSelection,
+    order: Vec<usize>,
+    sort: Option<(usize, SortOrder)>,
+    sort_changed: bool,
+}
+
+impl TableState {
+    pub fn new(columns: Vec<TableColumn>, mode: SelectionMode) -> Self {
+        let order = (0..columns.len()).collect();
+        Self {
+            columns,
+            selection: ListSelection::new(mode),
+            order,
+            sort: None,
+            sort_changed: false,
+        }
+    }
+
+    // the columns from left to right
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    // the sorted column, the rows handed to the table are expected to follow it
+    pub fn sort(&self) -> Option<(usize, SortOrder)> {
+        self.sort
+    }
+
+    pub fn set_sort(&mut self, sort: Option<(usize, SortOrder)>) {
+        self.sort_changed |= self.sort != sort;
+        self.sort = sort;
+    }
+
+    // the sorting was changed from the header during the last frame
+    pub fn is_sort_changed(&self) -> bool {
+        self.sort_changed
+    }
+
+    // ascending first, a second click on the same column reverses it
+    pub(crate) fn toggle_sort(&mut self, column: usize) {
+        let order = match self.sort {
+            Some((c, SortOrder::Ascending)) if c == column => SortOrder::Descending,
+            _ => SortOrder::Ascending,
+        };
+        self.set_sort(Some((column, order)));
+    }
+
+    // shows the column at the place of another one, shifting the columns in between
+    pub(crate) fn move_column(&mut self, column: usize, to: usize) {
+        let (Some(from), Some(to)) = (self.order.iter().position(|&c| c == column), self.order.iter().position(|&c| c == to)) else {
+            return;
+        };
+        let column = self.order.remove(from);
+        self.order.insert(to, column);
+    }
+
+    // columns added or removed since the last frame are appended to or dropped from the shown order
+    pub(crate) fn begin_frame(&mut self) {
+        self.sort_changed = false;
+        let count = self.columns.len();
+        self.order.retain(|&c| c < count);
+        for c in 0..count {
+            if !self.order.contains(&c) {
+                self.order.push(c);
+            }
+        }
+        if self.sort.is_some_and(|(c, _)| c >= count) {
+            self.sort = None;
+        }
+    }
+
+    pub(crate) fn widths(&self) -> Vec<i32> {
+        self.order.iter().map(|&c| self.columns[c].width).collect()
+    }
+}
+
+// the payload of a column header being dragged to another place
+pub(crate) struct ColumnMove(pub usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> TableState {
+        let columns = vec![TableColumn::new("A", 40), TableColumn::new("B", 40).sortable(), TableColumn::new("C", 40)];
+        TableState::new(columns, SelectionMode::Single)
+    }
+
+    #[test]
+    fn move_column() {
+        let mut state = state();
+        state.move_column(0, 2);
+        assert_eq!(state.order(), &[1, 2, 0]);
+        state.move_column(0, 1);
+        assert_eq!(state.order(), &[0, 1, 2]);
+        state.move_column(2, 2);
+        assert_eq!(state.order(), &[0, 1, 2]);
+        // a column that doesn't exist moves nothing
+        state.move_column(5, 0);
+        assert_eq!(state.order(), &[0, 1, 2]);
+        assert_eq!(state.widths(), vec![40, 40, 40]);
+    }
+
+    #[test]
+    fn toggle_sort() {
+        let mut state = state();
+        state.toggle_sort(1);
+        assert_eq!(state.sort(), Some((1, SortOrder::Ascending)));
+        assert!(state.is_sort_changed());
+        state.toggle_sort(1);
+        assert_eq!(state.sort(), Some((1, SortOrder::Descending)));
+        state.toggle_sort(1);
+        assert_eq!(state.sort(), Some((1, SortOrder::Ascending)));
+        state.toggle_sort(0);
+        assert_eq!(state.sort(), Some((0, SortOrder::Ascending)));
+        state.begin_frame();
+        assert!(!state.is_sort_changed());
+        state.set_sort(Some((0, SortOrder::Ascending)));
+        assert!(!state.is_sort_changed());
+    }
+
+    #[test]
+    fn columns_follow_the_state() {
+        let mut state = state();
+        state.move_column(2, 0);
+        state.toggle_sort(2);
+        state.columns.remove(2);
+        state.begin_frame();
+        assert_eq!(state.order(), &[0, 1]);
+        assert_eq!(state.sort(), None);
+        state.columns.push(TableColumn::new("D", 40));
+        state.begin_frame();
+        assert_eq!(state.order(), &[0, 1, 2]);
+    }
+
+    #[cfg(feature = "builder")]
+    #[test]
+    fn same_widget_in_two_columns() {
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 10, 300, 300));
+        let mut panel = ctx.new_panel("table");
+        let mut state = state();
+        let mut clicks = Vec::new();
+        let mut frame = |ctx: &mut testing::TestContext, clicks: &mut Vec<(usize, usize)>| {
+            ctx.frame(|ctx| {
+                ctx.window(&mut window, WidgetOption::NONE, |c| {
+                    c.set_row_widths_height(&[-1], -1);
+                    c.table(&mut panel, &mut state, 3, |c, row, column| {
+                        if c.button_ex("Edit", None, WidgetOption::NONE).is_submitted() {
+                            clicks.push((row, column));
+                        }
+                    });
+                });
+            });
+        };
+        // the second column of the first row, under the header
+        let (x, y) = (70, 70);
+        ctx.input.borrow_mut().mousemove(x, y);
+        frame(&mut ctx, &mut clicks);
+        frame(&mut ctx, &mut clicks);
+        ctx.input.borrow_mut().mousedown(x, y, MouseButton::LEFT);
+        frame(&mut ctx, &mut clicks);
+        ctx.input.borrow_mut().mouseup(x, y, MouseButton::LEFT);
+        frame(&mut ctx, &mut clicks);
+        assert_eq!(clicks, vec![(0, 1)]);
+    }
+
+    #[cfg(feature = "builder")]
+    #[test]
+    fn resize_survives_a_moving_state() {
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 10, 300, 300));
+        let mut panel = ctx.new_panel("table");
+        // the state lives in a new box every frame, the old ones stay alive so the address always changes
+        let mut states = vec![Box::new(state())];
+        let mut frame = |ctx: &mut testing::TestContext, states: &mut Vec<Box<TableState>>| {
+            let mut state = states.last().unwrap().clone();
+            ctx.frame(|ctx| {
+                ctx.window(&mut window, WidgetOption::NONE, |c| {
+                    c.set_row_widths_height(&[-1], -1);
+                    c.table(&mut panel, &mut state, 3, |c, _, _| c.label("cell"));
+                });
+            });
+            states.push(state);
+        };
+        // the gap between the first two columns of the header
+        let (x, y) = (62, 48);
+        ctx.input.borrow_mut().mousemove(x, y);
+        frame(&mut ctx, &mut states);
+        frame(&mut ctx, &mut states);
+        ctx.input.borrow_mut().mousedown(x, y, MouseButton::LEFT);
+        frame(&mut ctx, &mut states);
+        for step in 1..=4 {
+            ctx.input.borrow_mut().mousemove(x + step * 5, y);
+            frame(&mut ctx, &mut states);
+        }
+        ctx.input.borrow_mut().mouseup(x + 20, y, MouseButton::LEFT);
+        frame(&mut ctx, &mut states);
+        assert_eq!(states.last().unwrap().widths(), vec![60, 40, 40]);
+    }
+}
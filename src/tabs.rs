@@ -0,0 +1,371 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+use super::*;
+use std::collections::HashMap;
+
+// what a tab bar remembers between frames, kept by its container under the bar id
+#[derive(Clone, Default)]
+pub(crate) struct TabBarState {
+    active: Option<Id>,
+    // the tabs from left to right, a dragged tab is moved here
+    order: Vec<Id>,
+    scroll: i32,
+    closing: Option<Id>,
+    reveal: bool,
+    touched: bool,
+}
+
+impl TabBarState {
+    fn move_tab(&mut self, tab: Id, to: Id) {
+        let (Some(from), Some(to)) = (self.order.iter().position(|&t| t == tab), self.order.iter().position(|&t| t == to)) else {
+            return;
+        };
+        let tab = self.order.remove(from);
+        self.order.insert(to, tab);
+    }
+
+    // scrolls the least to show the tab between `x` and `x + width`, or its left edge when the view is narrower
+    fn scroll_to(&mut self, x: i32, width: i32, view: i32) {
+        self.scroll = min(max(self.scroll, x + width - view), x);
+    }
+}
+
+// tab bars of a container, the ones not drawn during a frame are forgotten at the start of the next one
+#[derive(Clone, Default)]
+pub(crate) struct TabBars {
+    bars: HashMap<Id, TabBarState>,
+}
+
+impl TabBars {
+    fn take(&mut self, id: Id) -> TabBarState {
+        self.bars.remove(&id).unwrap_or_default()
+    }
+
+    fn put(&mut self, id: Id, mut state: TabBarState) {
+        state.touched = true;
+        self.bars.insert(id, state);
+    }
+
+    pub(crate) fn prune(&mut self) {
+        self.bars.retain(|_, s| s.touched);
+        for s in self.bars.values_mut() {
+            s.touched = false;
+        }
+    }
+}
+
+struct Tab {
+    id: Id,
+    label: String,
+    closable: bool,
+}
+
+// the payload of a tab header being dragged to another place
+struct TabMove {
+    bar: Id,
+    tab: Id,
+}
+
+// the tabs declared inside `Container::tab_bar`, only the content of the active one is run
+pub struct TabBar<'a, PR> {
+    container: &'a mut Container<PR>,
+    id: Id,
+    state: TabBarState,
+    tabs: Vec<Tab>,
+}
+
+impl<'a, PR: Clone> TabBar<'a, PR> {
+    // a tab showing `f` below the bar while it is active, returns true when it is
+    pub fn tab<F: FnOnce(&mut Container<PR>)>(&mut self, label: &str, f: F) -> bool {
+        self.add(label, false, f)
+    }
+
+    // a tab with a close button, clicking it clears `open` and the tab is left out until `open` is set again
+    pub fn closable_tab<F: FnOnce(&mut Container<PR>)>(&mut self, label: &str, open: &mut bool, f: F) -> bool {
+        let id = IdManager::get_sub_id(self.id, label);
+        if self.state.closing == Some(id) {
+            self.state.closing = None;
+            *open = false;
+        }
+        if !*open {
+            return false;
+        }
+        self.add(label, true, f)
+    }
+
+    fn add<F: FnOnce(&mut Container<PR>)>(&mut self, label: &str, closable: bool, f: F) -> bool {
+        let id = IdManager::get_sub_id(self.id, label);
+        self.tabs.push(Tab { id, label: label.to_string(), closable });
+        // the first tab is active until another one is picked
+        if *self.state.active.get_or_insert(id) != id {
+            return false;
+        }
+        self.container.idmngr.push_id(id);
+        f(self.container);
+        self.container.idmngr.pop_id();
+        true
+    }
+}
+
+impl<PR: Clone> Container<PR> {
+    fn tab_width(&self, tab: &Tab, height: i32) -> i32 {
+        let text = self.atlas.get_text_size(self.style.font, tab.label.as_str()).width + self.style.padding * 2;
+        if tab.closable {
+            text + height
+        } else {
+            text
+        }
+    }
+
+    // a row of tab headers above the content of the active tab, declared with `TabBar::tab`. The active tab is
+    // remembered per id, the headers are dragged to reorder them and scroll with the arrows when they don't fit.
+    // Returns CHANGE when another tab was picked.
+    pub fn tab_bar<F: FnOnce(&mut TabBar<PR>)>(&mut self, id: &str, f: F) -> ResourceState {
+        let mut res = ResourceState::NONE;
+        let bar_id = self.idmngr.get_id_from_str(id);
+        self.layout.row(&[-1], 0);
+        let bar = self.layout.next();
+        let state = self.tabs.take(bar_id);
+        let mut tab_bar = TabBar {
+            container: self,
+            id: bar_id,
+            state,
+            tabs: Vec::new(),
+        };
+        f(&mut tab_bar);
+        let TabBar { mut state, tabs, .. } = tab_bar;
+
+        // tabs no longer declared give their place to their right neighbour
+        let previous = state.order.clone();
+        state.order.retain(|t| tabs.iter().any(|tab| tab.id == *t));
+        for tab in tabs.iter() {
+            if !state.order.contains(&tab.id) {
+                state.order.push(tab.id);
+            }
+        }
+        if state.active.is_some_and(|a| !state.order.contains(&a)) {
+            let at = previous.iter().position(|&t| Some(t) == state.active).unwrap_or(0);
+            state.active = state.order.get(min(at, state.order.len().saturating_sub(1))).copied();
+            state.reveal = true;
+            res |= ResourceState::CHANGE;
+        }
+
+        let spacing = self.style.spacing;
+        let widths: Vec<i32> = state
+            .order
+            .iter()
+            .map(|t| self.tab_width(tabs.iter().find(|tab| tab.id == *t).unwrap(), bar.height))
+            .collect();
+        let total = widths.iter().sum::<i32>() + spacing * (widths.len() as i32 - 1);
+        let arrow = bar.height;
+        let overflow = total > bar.width;
+        let view = if overflow {
+            rect(bar.x, bar.y, max(bar.width - (arrow + spacing) * 2, 0), bar.height)
+        } else {
+            bar
+        };
+
+        if overflow {
+            let left = rect(view.x + view.width + spacing, bar.y, arrow, bar.height);
+            let right = rect(left.x + arrow + spacing, bar.y, arrow, bar.height);
+            for (r, label, step) in [(left, "<", -view.width / 2), (right, ">", view.width / 2)] {
+                let id = IdManager::get_sub_id(bar_id, label);
+                self.update_control(id, r, WidgetOption::NONE);
+                if self.is_activated(id) {
+                    state.scroll += step;
+                }
+                self.draw_control_frame(id, r, ControlColor::Button, WidgetOption::NONE);
+                self.draw_control_text(label, r, ControlColor::Text, WidgetOption::ALIGN_CENTER);
+            }
+        }
+        // keeps the picked tab in view
+        if state.reveal {
+            state.reveal = false;
+            let at = state.order.iter().position(|&t| Some(t) == state.active);
+            if let Some(at) = at {
+                let x = widths[..at].iter().map(|w| w + spacing).sum();
+                state.scroll_to(x, widths[at], view.width);
+            }
+        }
+        state.scroll = state.scroll.clamp(0, max(total - view.width, 0));
+
+        self.push_clip_rect(view);
+        let mut x = view.x - state.scroll;
+        let order = state.order.clone();
+        for (t, &w) in order.iter().zip(widths.iter()) {
+            let tab = tabs.iter().find(|tab| tab.id == *t).unwrap();
+            let r = rect(x, bar.y, w, bar.height);
+            x += w + spacing;
+            self.update_control(tab.id, r, WidgetOption::NONE);
+            if self.is_activated(tab.id) && state.active != Some(tab.id) {
+                state.active = Some(tab.id);
+                state.reveal = true;
+                res |= ResourceState::CHANGE;
+            }
+            self.drag_source(tab.label.as_str(), || TabMove { bar: bar_id, tab: tab.id });
+            if let Some(TabMove { bar, tab: from }) = self.drop_target::<TabMove>() {
+                if bar == bar_id {
+                    state.move_tab(from, tab.id);
+                }
+            }
+
+            if state.active == Some(tab.id) {
+                self.draw_frame(r, ControlColor::ButtonFocus);
+            } else {
+                self.draw_control_frame(tab.id, r, ControlColor::Button, WidgetOption::NONE);
+            }
+            let close = rect(r.x + r.width - r.height, r.y, r.height, r.height);
+            let text = if tab.closable { rect(r.x, r.y, r.width - close.width, r.height) } else { r };
+            self.draw_control_text(tab.label.as_str(), text, ControlColor::Text, WidgetOption::NONE);
+            if tab.closable {
+                let close_id = IdManager::get_sub_id(tab.id, "!close");
+                self.update_control(close_id, close, WidgetOption::NO_NAV);
+                if self.is_activated(close_id) {
+                    state.closing = Some(tab.id);
+                }
+                let color = self.style.colors[ControlColor::Text as usize];
+                self.draw_icon(CLOSE_ICON, close, color);
+            }
+        }
+        self.pop_clip_rect();
+
+        self.tabs.put(bar_id, state);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(tabs: &[Id]) -> TabBarState {
+        TabBarState {
+            order: tabs.to_vec(),
+            ..TabBarState::default()
+        }
+    }
+
+    #[test]
+    fn move_tab() {
+        let mut ids = IdManager::new();
+        let (a, b, c, d) = (
+            ids.get_id_from_str("a"),
+            ids.get_id_from_str("b"),
+            ids.get_id_from_str("c"),
+            ids.get_id_from_str("d"),
+        );
+        let mut state = state(&[a, b, c]);
+        state.move_tab(a, c);
+        assert_eq!(state.order, vec![b, c, a]);
+        state.move_tab(a, b);
+        assert_eq!(state.order, vec![a, b, c]);
+        state.move_tab(c, c);
+        assert_eq!(state.order, vec![a, b, c]);
+        // a tab from elsewhere moves nothing
+        state.move_tab(d, a);
+        assert_eq!(state.order, vec![a, b, c]);
+    }
+
+    #[test]
+    fn scroll_to() {
+        let mut state = state(&[]);
+        state.scroll_to(150, 40, 100);
+        assert_eq!(state.scroll, 90);
+        state.scroll_to(20, 40, 100);
+        assert_eq!(state.scroll, 20);
+        state.scroll_to(60, 40, 100);
+        assert_eq!(state.scroll, 20);
+        // wider than the view, its left edge shows
+        state.scroll_to(200, 150, 100);
+        assert_eq!(state.scroll, 200);
+        state.scroll_to(0, 50, 0);
+        assert_eq!(state.scroll, 0);
+    }
+
+    #[cfg(feature = "builder")]
+    #[test]
+    fn narrow_bar() {
+        // runs a frame with two tabs wider than the view of the bar. Returns whether the second tab is active, where
+        // it starts in the bar and the scroll offset of the bar.
+        fn frame(ctx: &mut testing::TestContext, window: &mut WindowHandle<()>) -> (bool, i32, i32) {
+            let mut seen = (false, 0, 0);
+            ctx.frame(|ctx| {
+                ctx.window(window, WidgetOption::NONE, |c| {
+                    let bar_id = c.idmngr.get_id_from_str("tabs");
+                    c.tab_bar("tabs", |bar| {
+                        bar.tab("A rather long first tab", |_| {});
+                        seen.0 = bar.tab("And a second one", |_| {});
+                    });
+                    let state = &c.tabs.bars[&bar_id];
+                    let first = IdManager::get_sub_id(bar_id, "A rather long first tab");
+                    let width = c.tab_width(
+                        &Tab {
+                            id: first,
+                            label: "A rather long first tab".into(),
+                            closable: false,
+                        },
+                        0,
+                    );
+                    seen.1 = width + c.style.spacing;
+                    seen.2 = state.scroll;
+                });
+            });
+            seen
+        }
+
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 10, 100, 200));
+        let click = |ctx: &mut testing::TestContext, window: &mut WindowHandle<()>, x: i32| {
+            ctx.input.borrow_mut().mousemove(x, 49);
+            frame(ctx, window);
+            frame(ctx, window);
+            ctx.input.borrow_mut().mousedown(x, 49, MouseButton::LEFT);
+            frame(ctx, window);
+            ctx.input.borrow_mut().mouseup(x, 49, MouseButton::LEFT);
+            frame(ctx, window)
+        };
+        let (active, start, scroll) = frame(&mut ctx, &mut window);
+        assert_eq!((active, scroll), (false, 0));
+        // the bar is 90 wide at x 15, the arrows leave a view of 42 and scroll it by half of that
+        let (view_x, view_width) = (15, 42);
+        for _ in 0..5 {
+            click(&mut ctx, &mut window, view_x + view_width + 4 + 20 + 4 + 10);
+        }
+        let (_, _, scroll) = frame(&mut ctx, &mut window);
+        assert_eq!(scroll, 105);
+        // the second tab shows from x 35 on, it is wider than the view
+        let (active, _, scroll) = click(&mut ctx, &mut window, view_x + start - scroll + 5);
+        assert!(active);
+        assert_eq!(scroll, start);
+        assert!((0..view_width).contains(&(start - scroll)));
+        // and it stays there
+        assert_eq!(frame(&mut ctx, &mut window), (true, start, start));
+    }
+}
@@ -0,0 +1,65 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// a context drawing nowhere, for the tests driving widgets through input
+use super::*;
+
+pub(crate) struct NullRenderer {
+    atlas: AtlasHandle,
+}
+
+impl Renderer<()> for NullRenderer {
+    fn get_atlas(&self) -> AtlasHandle {
+        self.atlas.clone()
+    }
+    fn clear(&mut self, _width: i32, _height: i32, _clr: Color) {}
+    fn push_quad_vertices(&mut self, _v0: &Vertex, _v1: &Vertex, _v2: &Vertex, _v3: &Vertex) {}
+    fn flush(&mut self) {}
+    fn command(&mut self, _pr: &()) {}
+}
+
+pub(crate) fn context() -> TestContext {
+    let slots: Vec<Dimensioni> = Vec::new();
+    let config = builder::Config {
+        texture_height: 256,
+        texture_width: 256,
+        white_icon: "assets/WHITE.png".into(),
+        close_icon: "assets/CLOSE.png".into(),
+        expand_icon: "assets/PLUS.png".into(),
+        collapse_icon: "assets/MINUS.png".into(),
+        check_icon: "assets/CHECK.png".into(),
+        default_font: "assets/NORMAL.ttf".into(),
+        default_font_size: 12,
+        slots: &slots,
+    };
+    let atlas = builder::Builder::from_config(&config).unwrap().to_atlas();
+    Context::new(NullRenderer { atlas }, Dimensioni::new(800, 600))
+}
+
+pub(crate) type TestContext = Context<(), NullRenderer>;
@@ -0,0 +1,115 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+use super::*;
+
+// how long the mouse rests on a widget before its tooltip shows, in milliseconds
+const TOOLTIP_DELAY_MS: u64 = 500;
+// distance between the mouse and the tooltip
+const TOOLTIP_OFFSET: i32 = 16;
+
+pub(crate) struct Tooltip<PR> {
+    pub(crate) window: Option<WindowHandle<PR>>,
+    pub(crate) delay_ms: u64,
+    pub(crate) screen: Dimensioni,
+    // the hovered widget, keyed by its container since ids are only unique within one
+    hot: Option<(usize, Id)>,
+    since: u64,
+    claimed: bool,
+    drawn: usize,
+    visible: bool,
+}
+
+impl<PR> Tooltip<PR> {
+    pub(crate) fn new(screen: Dimensioni) -> Self {
+        Self {
+            window: None,
+            delay_ms: TOOLTIP_DELAY_MS,
+            screen,
+            hot: None,
+            since: 0,
+            claimed: false,
+            drawn: 0,
+            visible: false,
+        }
+    }
+}
+
+impl<PR: Clone> Tooltip<PR> {
+    // the tooltip window to draw into once the widget has been hovered long enough
+    pub(crate) fn hover(&mut self, key: (usize, Id), time: u64) -> Option<WindowHandle<PR>> {
+        if self.hot != Some(key) {
+            self.hot = Some(key);
+            self.since = time;
+            self.drawn = 0;
+        }
+        self.claimed = true;
+        if time.saturating_sub(self.since) < self.delay_ms {
+            return None;
+        }
+        // the first frame only measures the content, the auto sized window shows from the next one
+        self.drawn += 1;
+        self.visible = self.drawn > 1;
+        self.window.clone()
+    }
+
+    pub(crate) fn visible_window(&self) -> Option<WindowHandle<PR>> {
+        if self.visible {
+            self.window.clone()
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn end_frame(&mut self) {
+        if !self.claimed {
+            self.hot = None;
+        }
+        self.claimed = false;
+        self.visible = false;
+    }
+}
+
+// places a rectangle of the given size next to the mouse, flipping it above and left of the mouse near the screen edges
+pub(crate) fn tooltip_rect(mouse_pos: Vec2i, size: Dimensioni, screen: Dimensioni) -> Recti {
+    let mut x = mouse_pos.x + TOOLTIP_OFFSET;
+    let mut y = mouse_pos.y + TOOLTIP_OFFSET;
+    if x + size.width > screen.width {
+        x = mouse_pos.x - size.width;
+    }
+    if y + size.height > screen.height {
+        y = mouse_pos.y - size.height;
+    }
+    rect(
+        x.min(screen.width - size.width).max(0),
+        y.min(screen.height - size.height).max(0),
+        size.width,
+        size.height,
+    )
+}
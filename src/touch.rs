@@ -0,0 +1,396 @@
+//
+// Copyright 2022-Present (c) Raja Lehtihet & Wael El Oraiby
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+// this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+// this list of conditions and the following disclaimer in the documentation
+// and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors
+// may be used to endorse or promote products derived from this software without
+// specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+use super::*;
+
+// a finger that moves less than this is still tapping or long pressing
+const TOUCH_SLOP: i32 = 8;
+const TAP_MS: u64 = 300;
+
+#[derive(Clone, Copy, Debug)]
+pub struct TouchPoint {
+    pub id: u64,
+    pub pos: Vec2i,
+    pub start_pos: Vec2i,
+    pub start_time: u64,
+}
+
+impl TouchPoint {
+    fn is_still(&self) -> bool {
+        (self.pos.x - self.start_pos.x).abs() <= TOUCH_SLOP && (self.pos.y - self.start_pos.y).abs() <= TOUCH_SLOP
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Gesture {
+    Tap(Vec2i),
+    LongPress(Vec2i),
+    Scroll(Vec2i),
+    Pinch(Real),
+}
+
+// a single finger drives the mouse: a tap clicks the left button, dragging past the slop presses it at the start
+// point and a long press clicks the right button. Two fingers scroll and pinch instead.
+#[derive(Clone, Debug)]
+pub(crate) struct TouchState {
+    points: Vec<TouchPoint>,
+    pressed: bool,
+    long_pressed: bool,
+    two_fingers: Option<(Vec2i, Real)>,
+    scroll: Vec2i,
+    pinch: Real,
+    gestures: Vec<Gesture>,
+}
+
+impl Default for TouchState {
+    fn default() -> Self {
+        Self {
+            points: Vec::new(),
+            pressed: false,
+            long_pressed: false,
+            two_fingers: None,
+            scroll: Vec2i::default(),
+            pinch: 1.0,
+            gestures: Vec::new(),
+        }
+    }
+}
+
+impl TouchState {
+    pub(crate) fn is_idle(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    // midpoint and distance of the first two fingers
+    fn span(&self) -> Option<(Vec2i, Real)> {
+        match self.points.as_slice() {
+            [a, b, ..] => {
+                let mid = vec2((a.pos.x + b.pos.x) / 2, (a.pos.y + b.pos.y) / 2);
+                let (dx, dy) = ((a.pos.x - b.pos.x) as Real, (a.pos.y - b.pos.y) as Real);
+                Some((mid, (dx * dx + dy * dy).sqrt()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Input {
+    // the touch is recorded as given, the mouse events derived from it are not
+    pub fn touchdown(&mut self, id: u64, x: i32, y: i32) {
+        self.record(InputEvent::TouchDown(id, x, y));
+        self.synthesize(|input| input.touch_down(id, x, y));
+    }
+
+    pub fn touchmove(&mut self, id: u64, x: i32, y: i32) {
+        self.record(InputEvent::TouchMove(id, x, y));
+        self.synthesize(|input| input.touch_move(id, x, y));
+    }
+
+    pub fn touchup(&mut self, id: u64, x: i32, y: i32) {
+        self.record(InputEvent::TouchUp(id, x, y));
+        self.synthesize(|input| input.touch_up(id, x, y));
+    }
+
+    fn touch_down(&mut self, id: u64, x: i32, y: i32) {
+        let pos = vec2(x, y);
+        let time = self.time;
+        self.touch.points.push(TouchPoint {
+            id,
+            pos,
+            start_pos: pos,
+            start_time: time,
+        });
+        match self.touch.points.len() {
+            1 => {
+                self.touch.pressed = false;
+                self.touch.long_pressed = false;
+                self.mousemove(x, y);
+            }
+            2 => {
+                if self.touch.pressed {
+                    self.touch.pressed = false;
+                    self.mouseup(self.mouse_pos.x, self.mouse_pos.y, MouseButton::LEFT);
+                }
+                self.touch.two_fingers = self.touch.span();
+            }
+            _ => (),
+        }
+    }
+
+    fn touch_move(&mut self, id: u64, x: i32, y: i32) {
+        let index = match self.touch.points.iter().position(|p| p.id == id) {
+            Some(index) => index,
+            None => return,
+        };
+        self.touch.points[index].pos = vec2(x, y);
+
+        match (self.touch.points.len(), self.touch.two_fingers) {
+            (1, None) if !self.touch.long_pressed => {
+                let point = self.touch.points[0];
+                if self.touch.pressed {
+                    self.mousemove(x, y);
+                } else if !point.is_still() {
+                    self.touch.pressed = true;
+                    self.mousedown(point.start_pos.x, point.start_pos.y, MouseButton::LEFT);
+                }
+            }
+            (_, Some((last_mid, last_dist))) if index < 2 => {
+                if let Some((mid, dist)) = self.touch.span() {
+                    self.touch.scroll.x += last_mid.x - mid.x;
+                    self.touch.scroll.y += last_mid.y - mid.y;
+                    self.scroll(last_mid.x - mid.x, last_mid.y - mid.y);
+                    if last_dist > 0.0 {
+                        self.touch.pinch *= dist / last_dist;
+                    }
+                    self.touch.two_fingers = Some((mid, dist));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn touch_up(&mut self, id: u64, x: i32, y: i32) {
+        let index = match self.touch.points.iter().position(|p| p.id == id) {
+            Some(index) => index,
+            None => return,
+        };
+        let mut point = self.touch.points.remove(index);
+        point.pos = vec2(x, y);
+
+        if self.touch.two_fingers.is_some() {
+            // lifting one of two fingers ends the gesture without giving the mouse back to the other one
+            if self.touch.points.is_empty() {
+                self.touch.two_fingers = None;
+            }
+            return;
+        }
+        if self.touch.pressed {
+            self.touch.pressed = false;
+            self.mouseup(x, y, MouseButton::LEFT);
+        } else if !self.touch.long_pressed && point.is_still() && self.time.saturating_sub(point.start_time) <= TAP_MS {
+            self.mousedown(x, y, MouseButton::LEFT);
+            self.mouseup(x, y, MouseButton::LEFT);
+            self.touch.gestures.push(Gesture::Tap(point.pos));
+        }
+    }
+
+    pub fn touches(&self) -> &[TouchPoint] {
+        self.touch.points.as_slice()
+    }
+
+    pub fn gestures(&self) -> &[Gesture] {
+        self.touch.gestures.as_slice()
+    }
+
+    // relative change of the distance between two fingers since the last frame, 1 when nobody pinches
+    pub fn pinch_scale(&self) -> Real {
+        self.touch.pinch
+    }
+
+    pub(crate) fn touch_prelude(&mut self) {
+        if let ([point], false, false, None) = (
+            self.touch.points.as_slice(),
+            self.touch.pressed,
+            self.touch.long_pressed,
+            self.touch.two_fingers,
+        ) {
+            let point = *point;
+            if point.is_still() && self.time.saturating_sub(point.start_time) >= self.thresholds.long_press_ms {
+                self.touch.long_pressed = true;
+                self.mousedown(point.pos.x, point.pos.y, MouseButton::RIGHT);
+                self.mouseup(point.pos.x, point.pos.y, MouseButton::RIGHT);
+                self.touch.gestures.push(Gesture::LongPress(point.pos));
+            }
+        }
+        if self.touch.scroll.x != 0 || self.touch.scroll.y != 0 {
+            self.touch.gestures.push(Gesture::Scroll(self.touch.scroll));
+        }
+        if self.touch.pinch != 1.0 {
+            self.touch.gestures.push(Gesture::Pinch(self.touch.pinch));
+        }
+    }
+
+    pub(crate) fn touch_epilogue(&mut self) {
+        self.touch.gestures.clear();
+        self.touch.scroll = Vec2i::default();
+        self.touch.pinch = 1.0;
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+
+    // what a frame saw of the input: the gestures, the mouse buttons pressed and held, and the pinch scale
+    struct Seen {
+        gestures: Vec<Gesture>,
+        pressed: MouseButton,
+        down: MouseButton,
+        pinch: Real,
+        clicked: bool,
+    }
+
+    // runs a frame at `time` with a button filling the first row of a window at (10, 10)
+    fn frame(ctx: &mut testing::TestContext, window: &mut WindowHandle<()>, time: u64) -> Seen {
+        ctx.input.borrow_mut().set_time(time);
+        let mut seen = None;
+        ctx.frame(|ctx| {
+            let mut clicked = false;
+            ctx.window(window, WidgetOption::NONE, |c| {
+                c.set_row_widths_height(&[-1], 0);
+                clicked = c.button_ex("Button", None, WidgetOption::NONE).is_submitted();
+            });
+            let input = ctx.input.borrow();
+            seen = Some(Seen {
+                gestures: input.gestures().to_vec(),
+                pressed: input.mouse_pressed,
+                down: input.mouse_down,
+                pinch: input.pinch_scale(),
+                clicked,
+            });
+        });
+        seen.unwrap()
+    }
+
+    fn setup() -> (testing::TestContext, WindowHandle<()>) {
+        let mut ctx = testing::context();
+        let mut window = ctx.new_window("window", rect(10, 10, 300, 300));
+        frame(&mut ctx, &mut window, 1000);
+        (ctx, window)
+    }
+
+    #[test]
+    fn tap_clicks() {
+        let (mut ctx, mut window) = setup();
+        ctx.input.borrow_mut().touchdown(1, 60, 39);
+        let seen = frame(&mut ctx, &mut window, 1016);
+        assert!(seen.gestures.is_empty() && seen.pressed.is_none() && !seen.clicked);
+        frame(&mut ctx, &mut window, 1032);
+        // a wobble inside the slop is still a tap
+        ctx.input.borrow_mut().touchmove(1, 63, 41);
+        frame(&mut ctx, &mut window, 1048);
+        ctx.input.borrow_mut().touchup(1, 63, 41);
+        let seen = frame(&mut ctx, &mut window, 1100);
+        assert!(matches!(seen.gestures.as_slice(), [Gesture::Tap(p)] if (p.x, p.y) == (63, 41)));
+        assert!(seen.pressed.is_left() && seen.down.is_none() && seen.clicked);
+        assert!(frame(&mut ctx, &mut window, 1116).gestures.is_empty());
+    }
+
+    #[test]
+    fn slow_release_is_no_tap() {
+        let (mut ctx, mut window) = setup();
+        ctx.input.borrow_mut().touchdown(1, 60, 39);
+        frame(&mut ctx, &mut window, 1016);
+        ctx.input.borrow_mut().set_time(1016 + TAP_MS + 1);
+        ctx.input.borrow_mut().touchup(1, 60, 39);
+        let seen = frame(&mut ctx, &mut window, 1016 + TAP_MS + 1);
+        assert!(seen.gestures.is_empty() && seen.pressed.is_none());
+    }
+
+    #[test]
+    fn long_press_right_clicks_once() {
+        let (mut ctx, mut window) = setup();
+        let long_press_ms = ctx.input.borrow().thresholds.long_press_ms;
+        ctx.input.borrow_mut().touchdown(1, 60, 39);
+        frame(&mut ctx, &mut window, 1000);
+        let seen = frame(&mut ctx, &mut window, 1000 + long_press_ms - 1);
+        assert!(seen.gestures.is_empty() && seen.pressed.is_none());
+        let seen = frame(&mut ctx, &mut window, 1000 + long_press_ms);
+        assert!(matches!(seen.gestures.as_slice(), [Gesture::LongPress(p)] if (p.x, p.y) == (60, 39)));
+        assert!(seen.pressed.is_right() && seen.down.is_none());
+        assert!(frame(&mut ctx, &mut window, 1000 + long_press_ms * 2).gestures.is_empty());
+        // lifting the finger afterwards is no tap
+        ctx.input.borrow_mut().touchup(1, 60, 39);
+        let seen = frame(&mut ctx, &mut window, 1000 + long_press_ms * 2 + 16);
+        assert!(seen.gestures.is_empty() && seen.pressed.is_none());
+    }
+
+    #[test]
+    fn drag_presses_at_the_start() {
+        let (mut ctx, mut window) = setup();
+        ctx.input.borrow_mut().touchdown(1, 60, 39);
+        frame(&mut ctx, &mut window, 1016);
+        ctx.input.borrow_mut().touchmove(1, 60 + TOUCH_SLOP + 1, 39);
+        let seen = frame(&mut ctx, &mut window, 1032);
+        assert!(seen.pressed.is_left() && seen.down.is_left());
+        let pos = ctx.input.borrow().mouse_pos;
+        assert_eq!((pos.x, pos.y), (60, 39));
+        ctx.input.borrow_mut().touchmove(1, 100, 50);
+        frame(&mut ctx, &mut window, 1048);
+        let pos = ctx.input.borrow().mouse_pos;
+        assert_eq!((pos.x, pos.y), (100, 50));
+        ctx.input.borrow_mut().touchup(1, 100, 50);
+        let seen = frame(&mut ctx, &mut window, 1064);
+        assert!(seen.down.is_none() && seen.gestures.is_empty());
+    }
+
+    #[test]
+    fn second_finger_scrolls_and_pinches() {
+        let (mut ctx, mut window) = setup();
+        // the first finger drags, the second one releases the button it holds
+        ctx.input.borrow_mut().touchdown(1, 100, 100);
+        ctx.input.borrow_mut().touchmove(1, 100, 120);
+        assert!(frame(&mut ctx, &mut window, 1016).down.is_left());
+        ctx.input.borrow_mut().touchdown(2, 200, 120);
+        let seen = frame(&mut ctx, &mut window, 1032);
+        assert!(seen.down.is_none() && seen.gestures.is_empty());
+
+        // both fingers move up 10 pixels, the second one twice in the frame
+        {
+            let mut input = ctx.input.borrow_mut();
+            input.touchmove(1, 100, 110);
+            input.touchmove(2, 200, 115);
+            input.touchmove(2, 200, 110);
+        }
+        let seen = frame(&mut ctx, &mut window, 1048);
+        assert!(matches!(seen.gestures.as_slice(), [Gesture::Scroll(d)] if (d.x, d.y) == (0, 10)));
+        assert_eq!(seen.pinch, 1.0);
+        assert!(seen.down.is_none());
+
+        // the second finger moves away, the distance doubles around the same midpoint
+        {
+            let mut input = ctx.input.borrow_mut();
+            input.touchmove(1, 50, 110);
+            input.touchmove(2, 250, 110);
+        }
+        let seen = frame(&mut ctx, &mut window, 1064);
+        assert!(matches!(seen.gestures.as_slice(), [Gesture::Pinch(s)] if (*s - 2.0).abs() < 1e-5));
+        assert!((seen.pinch - 2.0).abs() < 1e-5);
+
+        // nothing moved: the gestures and the scale are reset
+        let seen = frame(&mut ctx, &mut window, 1080);
+        assert!(seen.gestures.is_empty() && seen.pinch == 1.0);
+
+        // lifting one finger doesn't give the mouse to the other one
+        ctx.input.borrow_mut().touchup(2, 250, 110);
+        ctx.input.borrow_mut().touchmove(1, 20, 200);
+        let seen = frame(&mut ctx, &mut window, 1096);
+        assert!(seen.gestures.is_empty() && seen.pressed.is_none() && seen.down.is_none());
+    }
+}
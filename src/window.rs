@@ -63,6 +63,7 @@ pub(crate) enum Activity {
 pub(crate) enum Type {
     Window,
     Popup,
+    Modal,
 }
 
 #[derive(Clone)]
@@ -73,8 +74,8 @@ pub(crate) struct Window<PR> {
 }
 
 impl<PR: Clone> Window<PR> {
-    pub fn window(name: &str, atlas: AtlasHandle, style: &Style, input: Rc<RefCell<Input>>, initial_rect: Recti) -> Self {
-        let mut main = Container::new(name, atlas, style, input);
+    pub fn window(name: &str, atlas: AtlasHandle, style: &Style, input: Rc<RefCell<Input>>, shared: Shared<PR>, initial_rect: Recti) -> Self {
+        let mut main = Container::new(name, atlas, style, input, shared);
         main.rect = initial_rect;
 
         Self {
@@ -84,8 +85,8 @@ impl<PR: Clone> Window<PR> {
         }
     }
 
-    pub fn popup(name: &str, atlas: AtlasHandle, style: &Style, input: Rc<RefCell<Input>>, initial_rect: Recti) -> Self {
-        let mut main = Container::new(name, atlas, style, input);
+    pub fn popup(name: &str, atlas: AtlasHandle, style: &Style, input: Rc<RefCell<Input>>, shared: Shared<PR>, initial_rect: Recti) -> Self {
+        let mut main = Container::new(name, atlas, style, input, shared);
         main.rect = initial_rect;
 
         Self {
@@ -95,6 +96,17 @@ impl<PR: Clone> Window<PR> {
         }
     }
 
+    pub fn modal(name: &str, atlas: AtlasHandle, style: &Style, input: Rc<RefCell<Input>>, shared: Shared<PR>, initial_rect: Recti) -> Self {
+        let mut main = Container::new(name, atlas, style, input, shared);
+        main.rect = initial_rect;
+
+        Self {
+            ty: Type::Modal,
+            activity: Activity::Closed,
+            main,
+        }
+    }
+
     pub fn is_popup(&self) -> bool {
         match self.ty {
             Type::Popup => true,
@@ -102,10 +114,15 @@ impl<PR: Clone> Window<PR> {
         }
     }
 
+    pub fn is_modal(&self) -> bool {
+        matches!(self.ty, Type::Modal)
+    }
+
     #[inline(never)]
     fn begin_window(&mut self, opt: WidgetOption) {
         let is_popup = self.is_popup();
         let container = &mut self.main;
+        container.nav.borrow_mut().reset();
         let mut body = container.rect;
         let r = body;
         if !opt.has_no_frame() {
@@ -119,7 +136,8 @@ impl<PR: Clone> Window<PR> {
             // TODO: Is this necessary?
             if !opt.has_no_title() {
                 let id = container.idmngr.get_id_from_str("!title");
-                container.update_control(id, tr, opt);
+                container.update_control(id, tr, opt | WidgetOption::NO_NAV);
+                container.set_control_cursor(id, CursorIcon::Move);
                 container.draw_control_text(
                     &container.name.clone(), /* TODO: cloning the string is expensive, go to a different approach */
                     tr,
@@ -139,18 +157,27 @@ impl<PR: Clone> Window<PR> {
                 tr.width -= r.width;
                 let color = container.style.colors[ControlColor::TitleText as usize];
                 container.draw_icon(CLOSE_ICON, r, color);
-                container.update_control(id, r, opt);
+                container.update_control(id, r, opt | WidgetOption::NO_NAV);
                 if container.input.borrow().mouse_pressed.is_left() && Some(id) == container.focus {
                     self.activity = Activity::Closed;
                 }
             }
         }
+        // the menu bar strip sits between the title and the body
+        container.menu_bar = None;
+        if opt.has_menu_bar() {
+            let height = container.style.default_cell_size.height + container.style.padding * 2;
+            container.menu_bar = Some(rect(body.x, body.y, body.width, height));
+            body.y += height;
+            body.height -= height;
+        }
         container.push_container_body(body, opt);
         if !opt.is_auto_sizing() {
             let sz = container.style.title_height;
             let id_2 = container.idmngr.get_id_from_str("!resize");
             let r_0 = rect(r.x + r.width - sz, r.y + r.height - sz, sz, sz);
-            container.update_control(id_2, r_0, opt);
+            container.update_control(id_2, r_0, opt | WidgetOption::NO_NAV);
+            container.set_control_cursor(id_2, CursorIcon::ResizeNWSE);
             if Some(id_2) == container.focus && container.input.borrow().mouse_down.is_left() {
                 container.rect.width = if 96 > container.rect.width + container.input.borrow().mouse_delta.x {
                     96
@@ -175,10 +202,13 @@ impl<PR: Clone> Window<PR> {
         }
         let body = container.body;
         container.push_clip_rect(body);
+        // neither is the window chrome
+        container.last_control = None;
     }
 
     fn end_window(&mut self) {
         let container = &mut self.main;
+        container.draw_nav_ring();
         container.pop_clip_rect();
     }
 }
@@ -186,13 +216,23 @@ impl<PR: Clone> Window<PR> {
 #[derive(Clone)]
 pub struct WindowHandle<PR>(Rc<RefCell<Window<PR>>>);
 
+impl<PR> PartialEq for WindowHandle<PR> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
 impl<PR: Clone> WindowHandle<PR> {
-    pub(crate) fn window(name: &str, atlas: AtlasHandle, style: &Style, input: Rc<RefCell<Input>>, initial_rect: Recti) -> Self {
-        Self(Rc::new(RefCell::new(Window::window(name, atlas, style, input, initial_rect))))
+    pub(crate) fn window(name: &str, atlas: AtlasHandle, style: &Style, input: Rc<RefCell<Input>>, shared: Shared<PR>, initial_rect: Recti) -> Self {
+        Self(Rc::new(RefCell::new(Window::window(name, atlas, style, input, shared, initial_rect))))
     }
 
-    pub(crate) fn popup(name: &str, atlas: AtlasHandle, style: &Style, input: Rc<RefCell<Input>>) -> Self {
-        Self(Rc::new(RefCell::new(Window::popup(name, atlas, style, input, Recti::new(0, 0, 0, 0)))))
+    pub(crate) fn popup(name: &str, atlas: AtlasHandle, style: &Style, input: Rc<RefCell<Input>>, shared: Shared<PR>) -> Self {
+        Self(Rc::new(RefCell::new(Window::popup(name, atlas, style, input, shared, Recti::new(0, 0, 0, 0)))))
+    }
+
+    pub(crate) fn modal(name: &str, atlas: AtlasHandle, style: &Style, input: Rc<RefCell<Input>>, shared: Shared<PR>, initial_rect: Recti) -> Self {
+        Self(Rc::new(RefCell::new(Window::modal(name, atlas, style, input, shared, initial_rect))))
     }
 
     pub fn is_open(&self) -> bool {
@@ -202,6 +242,10 @@ impl<PR: Clone> WindowHandle<PR> {
         }
     }
 
+    pub(crate) fn close(&mut self) {
+        self.inner_mut().activity = Activity::Closed;
+    }
+
     pub(crate) fn inner_mut<'a>(&'a mut self) -> RefMut<'a, Window<PR>> {
         self.0.borrow_mut()
     }
@@ -233,4 +277,46 @@ impl<PR: Clone> WindowHandle<PR> {
     pub(crate) fn end_window(&mut self) {
         self.inner_mut().end_window()
     }
+
+    // draws the tooltip content next to the mouse, outside of the context's window list
+    pub(crate) fn tooltip<F: FnOnce(&mut Container<PR>)>(&mut self, mouse_pos: Vec2i, screen: Dimensioni, f: F) {
+        let opt = WidgetOption::AUTO_SIZE | WidgetOption::NO_RESIZE | WidgetOption::NO_SCROLL | WidgetOption::NO_TITLE;
+        self.inner_mut().main.in_hover_root = false;
+        self.overlay(|size| tooltip_rect(mouse_pos, size, screen), opt, f);
+    }
+
+    // draws a context menu level next to the anchor
+    pub(crate) fn menu<F: FnOnce(&mut Container<PR>)>(&mut self, anchor: Recti, screen: Dimensioni, f: F) {
+        let opt = WidgetOption::AUTO_SIZE | WidgetOption::NO_RESIZE | WidgetOption::NO_SCROLL | WidgetOption::NO_TITLE;
+        self.overlay(|size| menu_rect(anchor, size, screen), opt, f);
+    }
+
+    // draws a menu of a menu bar below its title
+    pub(crate) fn pulldown<F: FnOnce(&mut Container<PR>)>(&mut self, anchor: Recti, screen: Dimensioni, f: F) {
+        let opt = WidgetOption::AUTO_SIZE | WidgetOption::NO_RESIZE | WidgetOption::NO_SCROLL | WidgetOption::NO_TITLE;
+        self.overlay(|size| pulldown_rect(anchor, size, screen), opt, f);
+    }
+
+    // draws a list as wide as the anchor below it, scrolling when its content is taller than `height`
+    pub(crate) fn dropdown<F: FnOnce(&mut Container<PR>)>(&mut self, anchor: Recti, height: i32, screen: Dimensioni, f: F) {
+        let opt = WidgetOption::NO_RESIZE | WidgetOption::NO_TITLE;
+        self.overlay(|_| dropdown_rect(anchor, height, screen), opt, f);
+    }
+
+    // draws a popup placed by `place`, which gets the size the auto sizing measured on the previous draw
+    fn overlay<P: FnOnce(Dimensioni) -> Recti, F: FnOnce(&mut Container<PR>)>(&mut self, place: P, opt: WidgetOption, f: F) {
+        let mut window = self.inner_mut();
+        window.activity = Activity::Open;
+        let container = &mut window.main;
+        container.prepare();
+        // same size the auto sizing will give, from the content measured on the previous draw
+        let width = container.content_size.x + container.rect.width - container.body.width;
+        let height = container.content_size.y + container.rect.height - container.body.height;
+        container.rect = place(Dimensioni::new(width, height));
+        container.begin_root();
+        window.begin_window(opt);
+        f(&mut window.main);
+        window.end_window();
+        window.main.end_root();
+    }
 }